@@ -0,0 +1,122 @@
+//! Headless benchmark of the game's collision detection with a growing number of gifteroids,
+//! once with the spatial grid broad phase and once with a single grid cell, i.e. testing every
+//! pair. Run with `cargo bench --bench collisions`.
+
+use std::time::Instant;
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+// The game is a binary, so the modules collision detection needs are pulled in directly. Only
+// parts of them are used here, and without a test harness their unit tests are left out.
+#[path = "../src/collision.rs"]
+#[allow(dead_code, unused_imports)]
+mod collision;
+#[path = "../src/spatial_grid.rs"]
+#[allow(dead_code, unused_imports)]
+mod spatial_grid;
+#[path = "../src/wrap.rs"]
+#[allow(dead_code, unused_imports)]
+mod wrap;
+
+use collision::{
+    Collider, ColliderShape, CollisionEvent, CollisionGrid, CollisionLayer, CollisionPlugin,
+    PreviousPosition,
+};
+use spatial_grid::SpatialGrid;
+use wrap::{PlayField, ScreenWrap};
+
+/// Stands in for the game's label the wrap systems are ordered after.
+#[derive(SystemLabel)]
+struct MovementSystem;
+
+const FIELD_HALF_SIZE: Vec2 = Vec2::new(960.0, 540.0);
+/// Same as the game's.
+const GRID_CELL_SIZE: f32 = 128.0;
+const ITERATIONS: u32 = 20;
+
+fn random_position(rng: &mut StdRng) -> Vec2 {
+    Vec2::new(
+        rng.gen_range(-FIELD_HALF_SIZE.x..FIELD_HALF_SIZE.x),
+        rng.gen_range(-FIELD_HALF_SIZE.y..FIELD_HALF_SIZE.y),
+    )
+}
+
+/// Gifteroids bouncing off each other like with elastic collisions, and half as many snowballs
+/// flying at them.
+fn collision_app(num_gifteroids: usize, cell_size: f32) -> App {
+    let mut app = App::new();
+    app.insert_resource(PlayField(Rect::from_center_half_size(
+        Vec2::ZERO,
+        FIELD_HALF_SIZE,
+    )))
+    .add_plugin(CollisionPlugin)
+    .insert_resource(CollisionGrid(SpatialGrid::new(cell_size)));
+
+    let mut rng = StdRng::seed_from_u64(123);
+    for _ in 0..num_gifteroids {
+        let half_extents = Vec2::splat(rng.gen_range(12.0..80.0));
+        app.world.spawn((
+            Transform::from_translation(random_position(&mut rng).extend(0.0)).with_rotation(
+                Quat::from_rotation_z(rng.gen_range(0.0..std::f32::consts::TAU)),
+            ),
+            Collider::new(
+                ColliderShape::OrientedBox { half_extents },
+                CollisionLayer::Gifteroid,
+            )
+            .with_mask(&[CollisionLayer::Gifteroid]),
+            ScreenWrap {
+                radius: half_extents.length(),
+            },
+        ));
+    }
+    for _ in 0..num_gifteroids / 2 {
+        let position = random_position(&mut rng);
+        app.world.spawn((
+            Transform::from_translation(position.extend(0.0)),
+            Collider::new(
+                ColliderShape::Circle { radius: 5.0 },
+                CollisionLayer::Snowball,
+            )
+            .with_mask(&[CollisionLayer::Gifteroid]),
+            PreviousPosition(position - Vec2::new(15.0, 0.0)),
+        ));
+    }
+    app
+}
+
+fn collisions_found(app: &App) -> usize {
+    let events = app.world.resource::<Events<CollisionEvent>>();
+    events.get_reader().iter(events).count()
+}
+
+fn main() {
+    println!(
+        "{:>10} {:>12} {:>14} {:>14}",
+        "gifteroids", "collisions", "all pairs", "grid"
+    );
+
+    for num_gifteroids in [50, 100, 200, 400, 800] {
+        // A cell that never ends covers everything, so every pair is tested.
+        let mut all_pairs_app = collision_app(num_gifteroids, f32::INFINITY);
+        let mut grid_app = collision_app(num_gifteroids, GRID_CELL_SIZE);
+
+        let [all_pairs_time, grid_time] = [&mut all_pairs_app, &mut grid_app].map(|app| {
+            // The first update sets up the schedule and is left out.
+            app.update();
+            let start = Instant::now();
+            for _ in 0..ITERATIONS {
+                app.update();
+            }
+            start.elapsed() / ITERATIONS
+        });
+
+        let collisions = collisions_found(&grid_app);
+        assert_eq!(collisions_found(&all_pairs_app), collisions);
+
+        println!(
+            "{:>10} {:>12} {:>14?} {:>14?}",
+            num_gifteroids, collisions, all_pairs_time, grid_time
+        );
+    }
+}
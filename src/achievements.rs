@@ -0,0 +1,189 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use tinyjson::JsonValue;
+
+use crate::{
+    gifteroids::GifteroidDestroyedEvent,
+    santa::SantaDestroyedEvent,
+    score::{RoundStats, RoundStatsSystem},
+    storage, GameState,
+};
+
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<AchievementUnlockedEvent>()
+            .insert_resource(UnlockedAchievements::load())
+            .add_system_set(
+                SystemSet::on_update(GameState::Game)
+                    .with_system(track_round_progress.after(RoundStatsSystem)),
+            )
+            .add_system_set(SystemSet::on_enter(GameState::Highscore).with_system(on_round_won));
+    }
+}
+
+const STORAGE_KEY: &str = "achievements";
+
+pub enum AchievementCondition {
+    Win,
+    WinWithoutLosingLife,
+    WinUnderSeconds(f32),
+    SantasInRound(u32),
+    GiftsInRound(u32),
+    /// Gifts caught in explosions rather than hit directly.
+    ExplosionKillsInRound(u32),
+}
+
+pub struct AchievementDefinition {
+    /// Stable identifier for persisting, never change it once released.
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub condition: AchievementCondition,
+}
+
+pub const ACHIEVEMENTS: [AchievementDefinition; 6] = [
+    AchievementDefinition {
+        id: "win",
+        name: "Christmas Cancelled",
+        description: "Win a round",
+        condition: AchievementCondition::Win,
+    },
+    AchievementDefinition {
+        id: "win_without_losing_life",
+        name: "Untouchable",
+        description: "Clear a round without losing a life",
+        condition: AchievementCondition::WinWithoutLosingLife,
+    },
+    AchievementDefinition {
+        id: "win_under_60_seconds",
+        name: "Express Delivery",
+        description: "Win in under 60 seconds",
+        condition: AchievementCondition::WinUnderSeconds(60.0),
+    },
+    AchievementDefinition {
+        id: "santas_3",
+        name: "Naughty List",
+        description: "Down 3 Santas in one round",
+        condition: AchievementCondition::SantasInRound(3),
+    },
+    AchievementDefinition {
+        id: "gifts_50",
+        name: "Gift Wrecker",
+        description: "Destroy 50 gifts in one round",
+        condition: AchievementCondition::GiftsInRound(50),
+    },
+    AchievementDefinition {
+        id: "explosion_kills_5",
+        name: "Chain Reaction",
+        description: "Catch 5 gifts in explosions in one round",
+        condition: AchievementCondition::ExplosionKillsInRound(5),
+    },
+];
+
+pub struct AchievementUnlockedEvent {
+    pub achievement: &'static AchievementDefinition,
+}
+
+/// Ids of the achievements unlocked on this device.
+#[derive(Resource, Default)]
+pub struct UnlockedAchievements(HashSet<String>);
+
+impl UnlockedAchievements {
+    fn load() -> Self {
+        let Some(contents) = storage::load(STORAGE_KEY) else {
+            return Self::default();
+        };
+        let ids = contents.parse::<JsonValue>().ok().and_then(|json| {
+            json.get::<Vec<JsonValue>>()?
+                .iter()
+                .map(|id| id.get::<String>().cloned())
+                .collect::<Option<HashSet<_>>>()
+        });
+        match ids {
+            Some(ids) => Self(ids),
+            None => {
+                warn!("Ignoring malformed achievements");
+                Self::default()
+            }
+        }
+    }
+
+    fn save(&self) {
+        let ids = self
+            .0
+            .iter()
+            .map(|id| JsonValue::from(id.clone()))
+            .collect::<Vec<_>>();
+        storage::save(STORAGE_KEY, &JsonValue::from(ids).stringify().unwrap());
+    }
+
+    pub fn count(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Unlocks every achievement whose condition holds that isn't unlocked yet.
+    fn unlock_where(
+        &mut self,
+        condition_met: impl Fn(&AchievementCondition) -> bool,
+        events: &mut EventWriter<AchievementUnlockedEvent>,
+    ) {
+        let mut unlocked_any = false;
+        for achievement in ACHIEVEMENTS.iter() {
+            if self.0.contains(achievement.id) || !condition_met(&achievement.condition) {
+                continue;
+            }
+            info!("Achievement unlocked: {}", achievement.name);
+            self.0.insert(achievement.id.to_string());
+            events.send(AchievementUnlockedEvent { achievement });
+            unlocked_any = true;
+        }
+        if unlocked_any {
+            self.save();
+        }
+    }
+}
+
+/// Whether the condition holds for the round so far, win conditions only once it was won.
+fn condition_met(condition: &AchievementCondition, stats: &RoundStats, won: bool) -> bool {
+    match *condition {
+        AchievementCondition::Win => won,
+        AchievementCondition::WinWithoutLosingLife => won && stats.lives_lost == 0,
+        AchievementCondition::WinUnderSeconds(max_seconds) => won && stats.seconds < max_seconds,
+        AchievementCondition::SantasInRound(santas) => stats.santas_downed >= santas,
+        AchievementCondition::GiftsInRound(gifts) => {
+            stats.gifts_destroyed.iter().sum::<u32>() >= gifts
+        }
+        AchievementCondition::ExplosionKillsInRound(kills) => stats.explosion_kills >= kills,
+    }
+}
+
+fn track_round_progress(
+    mut gifteroid_events: EventReader<GifteroidDestroyedEvent>,
+    mut santa_events: EventReader<SantaDestroyedEvent>,
+    stats: Res<RoundStats>,
+    mut unlocked: ResMut<UnlockedAchievements>,
+    mut unlocked_events: EventWriter<AchievementUnlockedEvent>,
+) {
+    // Only destroyed gifts and Santas bring the in-round conditions closer.
+    if gifteroid_events.iter().count() + santa_events.iter().count() == 0 {
+        return;
+    }
+    unlocked.unlock_where(
+        |condition| condition_met(condition, &stats, false),
+        &mut unlocked_events,
+    );
+}
+
+fn on_round_won(
+    stats: Res<RoundStats>,
+    mut unlocked: ResMut<UnlockedAchievements>,
+    mut unlocked_events: EventWriter<AchievementUnlockedEvent>,
+) {
+    unlocked.unlock_where(
+        |condition| condition_met(condition, &stats, true),
+        &mut unlocked_events,
+    );
+}
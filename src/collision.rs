@@ -1,26 +1,764 @@
-use crate::gifteroids::OrientedBox;
-use bevy::prelude::Vec2;
-
-pub fn point_in_obb(obb: &OrientedBox, bb_pos: Vec2, point: Vec2) -> bool {
-    let box_lensq0 = obb.axis0.length_squared();
-    let box_lensq1 = obb.axis1.length_squared();
-    let to_bb = bb_pos - point;
-    let dot0 = obb.axis0.dot(to_bb);
-    let dot1 = obb.axis1.dot(to_bb);
-    dot0 > -box_lensq0 && dot0 < box_lensq0 && dot1 > -box_lensq1 && dot1 < box_lensq1
-}
-
-pub fn line_line_test(start0: Vec2, end0: Vec2, start1: Vec2, end1: Vec2) -> bool {
-    let dir0 = end0 - start0;
-    let dir1 = end1 - start1;
-    let denominator = (dir0.x * dir1.y) - (dir0.y * dir1.x);
-    let numerator1 = ((start0.y - start1.y) * dir1.x) - ((start0.x - start1.x) * dir1.y);
-    let numerator2 = ((start0.y - start1.y) * dir0.x) - ((start0.x - start1.x) * dir0.y);
-    if denominator == 0. {
-        return numerator1 == 0. && numerator2 == 0.;
-    }
-
-    let r = numerator1 / denominator;
-    let s = numerator2 / denominator;
-    (0.0..=1.0).contains(&r) && (0.0..=1.0).contains(&s)
+use std::{collections::HashSet, sync::Arc};
+
+use crate::{
+    spatial_grid::SpatialGrid,
+    wrap::{PlayField, ScreenWrap, WrapSystem},
+};
+use bevy::prelude::*;
+
+pub struct CollisionPlugin;
+
+impl Plugin for CollisionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CollisionEvent>()
+            .init_resource::<CollisionSettings>()
+            .insert_resource(CollisionGrid(SpatialGrid::new(GRID_CELL_SIZE)))
+            .add_system(detect_collisions.label(CollisionSystem).after(WrapSystem))
+            .add_system_to_stage(CoreStage::PostUpdate, track_previous_positions);
+    }
+}
+
+const GRID_CELL_SIZE: f32 = 128.0;
+
+#[derive(Resource, Default)]
+pub struct CollisionSettings {
+    /// Colliders with a [`PixelMask`] only hit where their sprites' solid pixels overlap.
+    pub pixel_perfect: bool,
+}
+
+/// Detects collisions and sends [`CollisionEvent`]s, systems reacting to them should run after it.
+#[derive(SystemLabel)]
+pub struct CollisionSystem;
+
+/// Sent once per frame for every pair of overlapping colliders where at least one of them
+/// has the other's layer in its mask.
+pub struct CollisionEvent {
+    pub a: Entity,
+    pub b: Entity,
+}
+
+impl CollisionEvent {
+    /// The pair with the entity for which `is_first` holds first, if there is one.
+    pub fn sorted(&self, is_first: impl Fn(Entity) -> bool) -> Option<(Entity, Entity)> {
+        if is_first(self.a) {
+            Some((self.a, self.b))
+        } else if is_first(self.b) {
+            Some((self.b, self.a))
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CollisionLayer {
+    SpaceShip = 1 << 0,
+    Gifteroid = 1 << 1,
+    Snowball = 1 << 2,
+    Santa = 1 << 3,
+    PowerUp = 1 << 4,
+}
+
+/// Bits of all given layers, for [`Collider::mask`].
+pub fn layer_mask(layers: &[CollisionLayer]) -> u32 {
+    layers.iter().fold(0, |mask, layer| mask | *layer as u32)
+}
+
+/// Shape in the entity's local space, i.e. before its transform's rotation and scale are applied.
+#[derive(Clone)]
+pub enum ColliderShape {
+    Circle {
+        radius: f32,
+    },
+    OrientedBox {
+        half_extents: Vec2,
+    },
+    #[allow(dead_code)] // Not used by any of the current sprites.
+    Triangle([Vec2; 3]),
+    /// Corners in either winding order.
+    ConvexPolygon(Vec<Vec2>),
+}
+
+impl ColliderShape {
+    /// Radius of a circle around the shape, in local space.
+    pub fn bounding_radius(&self) -> f32 {
+        match self {
+            ColliderShape::Circle { radius } => *radius,
+            ColliderShape::OrientedBox { half_extents } => half_extents.length(),
+            ColliderShape::Triangle(corners) => {
+                corners.iter().map(|c| c.length()).fold(0.0, f32::max)
+            }
+            ColliderShape::ConvexPolygon(corners) => {
+                corners.iter().map(|c| c.length()).fold(0.0, f32::max)
+            }
+        }
+    }
+}
+
+#[derive(Component, Clone)]
+pub struct Collider {
+    pub shape: ColliderShape,
+    pub layer: CollisionLayer,
+    /// Bits of all layers this collider reports collisions with.
+    pub mask: u32,
+    /// Refines hits of the shape if [`CollisionSettings::pixel_perfect`] is on.
+    pub pixel_mask: Option<Arc<PixelMask>>,
+}
+
+impl Collider {
+    pub fn new(shape: ColliderShape, layer: CollisionLayer) -> Self {
+        Self {
+            shape,
+            layer,
+            mask: 0,
+            pixel_mask: None,
+        }
+    }
+
+    pub fn with_mask(mut self, layers: &[CollisionLayer]) -> Self {
+        self.mask = layer_mask(layers);
+        self
+    }
+
+    fn interacts_with(&self, other: &Collider) -> bool {
+        self.mask & other.layer as u32 != 0 || other.mask & self.layer as u32 != 0
+    }
+
+    pub fn world_shape(&self, transform: &Transform) -> WorldShape {
+        let to_world = |point: &Vec2| transform.transform_point(point.extend(0.0)).truncate();
+        match &self.shape {
+            ColliderShape::Circle { radius } => WorldShape::Circle {
+                center: transform.translation.truncate(),
+                radius: radius * transform.scale.x.abs(),
+            },
+            ColliderShape::OrientedBox { half_extents } => WorldShape::Polygon(
+                [
+                    Vec2::new(half_extents.x, half_extents.y),
+                    Vec2::new(-half_extents.x, half_extents.y),
+                    Vec2::new(-half_extents.x, -half_extents.y),
+                    Vec2::new(half_extents.x, -half_extents.y),
+                ]
+                .iter()
+                .map(to_world)
+                .collect(),
+            ),
+            ColliderShape::Triangle(corners) => {
+                WorldShape::Polygon(corners.iter().map(to_world).collect())
+            }
+            ColliderShape::ConvexPolygon(corners) => {
+                WorldShape::Polygon(corners.iter().map(to_world).collect())
+            }
+        }
+    }
+}
+
+/// Downsampled alpha channel of a sprite, in sprite space like [`ColliderShape`].
+pub struct PixelMask {
+    /// Number of cells in x and y.
+    size: UVec2,
+    /// Size of a cell in sprite pixels.
+    cell_size: Vec2,
+    /// Row by row, starting at the top.
+    solid: Vec<bool>,
+}
+
+impl PixelMask {
+    pub fn new(size: UVec2, cell_size: Vec2, solid: Vec<bool>) -> Self {
+        assert_eq!(solid.len(), (size.x * size.y) as usize);
+        Self {
+            size,
+            cell_size,
+            solid,
+        }
+    }
+
+    fn half_extent(&self) -> Vec2 {
+        self.size.as_vec2() * self.cell_size * 0.5
+    }
+
+    fn is_solid(&self, point: Vec2) -> bool {
+        let half_extent = self.half_extent();
+        let column = ((point.x + half_extent.x) / self.cell_size.x).floor();
+        let row = ((half_extent.y - point.y) / self.cell_size.y).floor();
+        if column < 0.0 || row < 0.0 || column >= self.size.x as f32 || row >= self.size.y as f32 {
+            return false;
+        }
+        self.solid[row as usize * self.size.x as usize + column as usize]
+    }
+
+    fn solid_cell_centers(&self) -> impl Iterator<Item = Vec2> + '_ {
+        let half_extent = self.half_extent();
+        self.solid
+            .iter()
+            .enumerate()
+            .filter(|(_, solid)| **solid)
+            .map(move |(i, _)| {
+                let (column, row) = (i as u32 % self.size.x, i as u32 / self.size.x);
+                Vec2::new(
+                    (column as f32 + 0.5) * self.cell_size.x - half_extent.x,
+                    half_extent.y - (row as f32 + 0.5) * self.cell_size.y,
+                )
+            })
+    }
+}
+
+/// Position at the end of the last frame. Circle colliders with it are tested along their path,
+/// so fast objects can't tunnel through others.
+#[derive(Component)]
+pub struct PreviousPosition(pub Vec2);
+
+/// Collider shape placed in the world.
+pub enum WorldShape {
+    Circle { center: Vec2, radius: f32 },
+    Polygon(Vec<Vec2>),
+}
+
+impl WorldShape {
+    /// Covers the whole path of a circle moving from `previous_position` to its current center.
+    fn swept(self, previous_position: Vec2) -> Self {
+        let WorldShape::Circle { center, radius } = self else {
+            return self;
+        };
+        let Some(dir) = (center - previous_position).try_normalize() else {
+            return self;
+        };
+        let front = center + dir * radius;
+        let back = previous_position - dir * radius;
+        let side = dir.perp() * radius;
+        WorldShape::Polygon(vec![front + side, back + side, back - side, front - side])
+    }
+
+    fn translated(&self, offset: Vec2) -> Self {
+        match self {
+            WorldShape::Circle { center, radius } => WorldShape::Circle {
+                center: *center + offset,
+                radius: *radius,
+            },
+            WorldShape::Polygon(corners) => {
+                WorldShape::Polygon(corners.iter().map(|corner| *corner + offset).collect())
+            }
+        }
+    }
+
+    pub fn aabb(&self) -> (Vec2, Vec2) {
+        match self {
+            WorldShape::Circle { center, radius } => (*center - *radius, *center + *radius),
+            WorldShape::Polygon(corners) => corners.iter().fold(
+                (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+                |(min, max), corner| (min.min(*corner), max.max(*corner)),
+            ),
+        }
+    }
+
+    /// Center and radius of a circle around the shape.
+    pub fn bounding_circle(&self) -> (Vec2, f32) {
+        let (min, max) = self.aabb();
+        ((min + max) * 0.5, (max - min).length() * 0.5)
+    }
+
+    /// Corners of the shape's outline, circles are approximated with the given number of segments.
+    #[cfg(feature = "debug_lines")]
+    pub fn outline(&self, circle_segments: usize) -> Vec<Vec2> {
+        match self {
+            WorldShape::Circle { center, radius } => (0..circle_segments)
+                .map(|i| {
+                    let angle = i as f32 / circle_segments as f32 * std::f32::consts::TAU;
+                    *center + Vec2::new(angle.cos(), angle.sin()) * *radius
+                })
+                .collect(),
+            WorldShape::Polygon(corners) => corners.clone(),
+        }
+    }
+
+    fn intersects(&self, other: &WorldShape) -> bool {
+        match (self, other) {
+            (
+                WorldShape::Circle { center, radius },
+                WorldShape::Circle {
+                    center: other_center,
+                    radius: other_radius,
+                },
+            ) => center.distance_squared(*other_center) <= (radius + other_radius).powi(2),
+            (WorldShape::Circle { center, radius }, WorldShape::Polygon(corners))
+            | (WorldShape::Polygon(corners), WorldShape::Circle { center, radius }) => {
+                circle_polygon_intersect(*center, *radius, corners)
+            }
+            (WorldShape::Polygon(corners), WorldShape::Polygon(other_corners)) => {
+                convex_polygons_intersect(corners, other_corners)
+            }
+        }
+    }
+}
+
+/// Broad phase for all colliders including their ghosts on the other side of the play field,
+/// rebuilt every frame. Items are indices into the collider list of that frame.
+#[derive(Resource)]
+pub struct CollisionGrid(pub SpatialGrid<usize>);
+
+type ColliderComponents<'a> = (
+    Entity,
+    &'a Transform,
+    &'a Collider,
+    Option<&'a PreviousPosition>,
+    Option<&'a ScreenWrap>,
+);
+
+fn detect_collisions(
+    mut grid: ResMut<CollisionGrid>,
+    play_field: Res<PlayField>,
+    settings: Res<CollisionSettings>,
+    query: Query<ColliderComponents>,
+    mut collision_events: EventWriter<CollisionEvent>,
+    mut tested_pairs: Local<HashSet<(usize, usize)>>,
+) {
+    let colliders: Vec<_> = query
+        .iter()
+        .map(|(entity, transform, collider, previous_position, wrap)| {
+            let mut shape = collider.world_shape(transform);
+            if let Some(previous_position) = previous_position {
+                shape = shape.swept(previous_position.0);
+            }
+            let offsets = if wrap.is_some() {
+                let (center, radius) = shape.bounding_circle();
+                play_field.ghost_offsets(center, radius)
+            } else {
+                vec![Vec2::ZERO]
+            };
+            (entity, collider, transform, shape, offsets)
+        })
+        .collect();
+
+    let grid = &mut grid.0;
+    grid.clear();
+    for (index, (_, _, _, shape, offsets)) in colliders.iter().enumerate() {
+        let (min, max) = shape.aabb();
+        for offset in offsets {
+            grid.insert(min + *offset, max + *offset, index);
+        }
+    }
+
+    // Only ghosts are in the grid twice, so a pair may be found from either side.
+    tested_pairs.clear();
+    for (index, (entity, collider, transform, shape, offsets)) in colliders.iter().enumerate() {
+        let (min, max) = shape.aabb();
+        for &other_index in grid.query(min, max) {
+            let pair = (index.min(other_index), index.max(other_index));
+            if other_index == index || !tested_pairs.insert(pair) {
+                continue;
+            }
+            let (other_entity, other_collider, other_transform, other_shape, other_offsets) =
+                &colliders[other_index];
+            if !collider.interacts_with(other_collider) {
+                continue;
+            }
+
+            // Moving one shape by an offset is the same as moving the other by the negated offset.
+            let hit = other_offsets
+                .iter()
+                .copied()
+                .chain(offsets.iter().map(|offset| -*offset))
+                .any(|offset| {
+                    let other_shape = other_shape.translated(offset);
+                    let other_transform = Transform {
+                        translation: other_transform.translation + offset.extend(0.0),
+                        ..**other_transform
+                    };
+                    shape.intersects(&other_shape)
+                        && (!settings.pixel_perfect
+                            || pixel_masks_intersect(
+                                (collider, transform, shape),
+                                (other_collider, &other_transform, &other_shape),
+                            ))
+                });
+            if hit {
+                collision_events.send(CollisionEvent {
+                    a: *entity,
+                    b: *other_entity,
+                });
+            }
+        }
+    }
+}
+
+/// Fine test for colliders whose shapes already overlap. Samples the solid cells of one mask
+/// against the other mask, or against the other shape if that one has no mask.
+fn pixel_masks_intersect(
+    a: (&Collider, &Transform, &WorldShape),
+    b: (&Collider, &Transform, &WorldShape),
+) -> bool {
+    let ((mask, transform), (other_collider, other_transform, other_shape)) =
+        match (&a.0.pixel_mask, &b.0.pixel_mask) {
+            (Some(mask), _) => ((mask, a.1), b),
+            (None, Some(mask)) => ((mask, b.1), a),
+            (None, None) => return true,
+        };
+
+    let cell_radius = mask.cell_size.max_element() * 0.5 * transform.scale.abs().max_element();
+    let to_other_sprite_space = other_transform.compute_matrix().inverse();
+    mask.solid_cell_centers().any(|cell| {
+        let position = transform.transform_point(cell.extend(0.0));
+        match &other_collider.pixel_mask {
+            Some(other_mask) => {
+                other_mask.is_solid(to_other_sprite_space.transform_point3(position).truncate())
+            }
+            None => other_shape.intersects(&WorldShape::Circle {
+                center: position.truncate(),
+                radius: cell_radius,
+            }),
+        }
+    })
+}
+
+fn track_previous_positions(mut query: Query<(&Transform, &mut PreviousPosition)>) {
+    for (transform, mut previous_position) in &mut query {
+        previous_position.0 = transform.translation.truncate();
+    }
+}
+
+/// Separating axis test for two convex polygons, given by their corners in either winding order.
+/// Touching polygons count as intersecting.
+pub fn convex_polygons_intersect(polygon0: &[Vec2], polygon1: &[Vec2]) -> bool {
+    !has_separating_edge_normal(polygon0, polygon1)
+        && !has_separating_edge_normal(polygon1, polygon0)
+}
+
+fn has_separating_edge_normal(polygon: &[Vec2], other: &[Vec2]) -> bool {
+    let project = |points: &[Vec2], axis: Vec2| {
+        points
+            .iter()
+            .map(|point| point.dot(axis))
+            .fold((f32::MAX, f32::MIN), |(min, max), d| {
+                (min.min(d), max.max(d))
+            })
+    };
+
+    polygon.iter().enumerate().any(|(i, start)| {
+        let end = polygon[(i + 1) % polygon.len()];
+        let axis = (end - *start).perp();
+        let (min0, max0) = project(polygon, axis);
+        let (min1, max1) = project(other, axis);
+        max0 < min1 || max1 < min0
+    })
+}
+
+/// Whether a circle touches a convex polygon, given by its corners in either winding order.
+pub fn circle_polygon_intersect(center: Vec2, radius: f32, polygon: &[Vec2]) -> bool {
+    let edges = || {
+        polygon
+            .iter()
+            .enumerate()
+            .map(|(i, start)| (*start, polygon[(i + 1) % polygon.len()]))
+    };
+
+    // The center is inside if it is on the same side of all edges.
+    let (min_side, max_side) = edges()
+        .map(|(start, end)| (end - start).perp_dot(center - start))
+        .fold((f32::MAX, f32::MIN), |(min, max), side| {
+            (min.min(side), max.max(side))
+        });
+    if min_side >= 0.0 || max_side <= 0.0 {
+        return true;
+    }
+
+    edges().any(|(start, end)| {
+        let edge = end - start;
+        let t =
+            ((center - start).dot(edge) / edge.length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+        (start + edge * t).distance_squared(center) <= radius * radius
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    /// Whether a point is inside or on the outline of a convex polygon in either winding order.
+    fn polygon_contains(polygon: &[Vec2], point: Vec2) -> bool {
+        let mut sides = polygon
+            .iter()
+            .enumerate()
+            .map(|(i, start)| (polygon[(i + 1) % polygon.len()] - *start).perp_dot(point - *start));
+        sides.clone().all(|side| side >= 0.0) || sides.all(|side| side <= 0.0)
+    }
+
+    /// Reference for the separating axis test: looks for a point both polygons contain on a fine
+    /// grid. Corners on whole numbers are hit exactly, so touching outlines are found as well.
+    fn rasterized_overlap(polygon0: &[Vec2], polygon1: &[Vec2]) -> bool {
+        const STEP: f32 = 1.0 / 16.0;
+        let (min, max) = polygon0.iter().chain(polygon1).fold(
+            (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+            |(min, max), corner| (min.min(*corner), max.max(*corner)),
+        );
+        let steps = ((max - min) / STEP).ceil().as_uvec2();
+        (0..=steps.y).any(|y| {
+            (0..=steps.x).any(|x| {
+                let point = min + Vec2::new(x as f32, y as f32) * STEP;
+                polygon_contains(polygon0, point) && polygon_contains(polygon1, point)
+            })
+        })
+    }
+
+    fn square(min: Vec2, max: Vec2) -> Vec<Vec2> {
+        vec![min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)]
+    }
+
+    fn reversed(polygon: &[Vec2]) -> Vec<Vec2> {
+        polygon.iter().rev().copied().collect()
+    }
+
+    /// Corners of an oriented box collider at the origin, rotated counter-clockwise by `angle`.
+    fn rotated_box(half_extents: Vec2, angle: f32) -> Vec<Vec2> {
+        let collider = Collider::new(
+            ColliderShape::OrientedBox { half_extents },
+            CollisionLayer::Gifteroid,
+        );
+        match collider.world_shape(&Transform::from_rotation(Quat::from_rotation_z(angle))) {
+            WorldShape::Polygon(corners) => corners,
+            WorldShape::Circle { .. } => unreachable!(),
+        }
+    }
+
+    /// How deep the polygons overlap along the axis they overlap least on, negative for the gap
+    /// between separate ones.
+    fn overlap_depth(polygon0: &[Vec2], polygon1: &[Vec2]) -> f32 {
+        let project = |points: &[Vec2], axis: Vec2| {
+            points
+                .iter()
+                .map(|point| point.dot(axis))
+                .fold((f32::MAX, f32::MIN), |(min, max), d| {
+                    (min.min(d), max.max(d))
+                })
+        };
+        [polygon0, polygon1]
+            .iter()
+            .flat_map(|polygon| {
+                polygon
+                    .iter()
+                    .enumerate()
+                    .map(|(i, start)| (polygon[(i + 1) % polygon.len()] - *start).perp())
+            })
+            .map(|axis| {
+                let axis = axis.normalize();
+                let (min0, max0) = project(polygon0, axis);
+                let (min1, max1) = project(polygon1, axis);
+                max0.min(max1) - min0.max(min1)
+            })
+            .fold(f32::MAX, f32::min)
+    }
+
+    /// Checks the separating axis test against the rasterized overlap in both argument orders
+    /// and winding orders.
+    fn assert_matches_raster(polygon0: &[Vec2], polygon1: &[Vec2], expected: bool) {
+        assert_eq!(rasterized_overlap(polygon0, polygon1), expected);
+        for (a, b) in [
+            (polygon0.to_vec(), polygon1.to_vec()),
+            (polygon1.to_vec(), polygon0.to_vec()),
+            (reversed(polygon0), polygon1.to_vec()),
+            (polygon0.to_vec(), reversed(polygon1)),
+        ] {
+            assert_eq!(convex_polygons_intersect(&a, &b), expected);
+        }
+    }
+
+    #[test]
+    fn overlapping_squares_intersect() {
+        assert_matches_raster(
+            &square(Vec2::new(0.0, 0.0), Vec2::new(2.0, 2.0)),
+            &square(Vec2::new(1.0, 1.0), Vec2::new(3.0, 3.0)),
+            true,
+        );
+    }
+
+    #[test]
+    fn separate_squares_dont_intersect() {
+        assert_matches_raster(
+            &square(Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0)),
+            &square(Vec2::new(2.0, 0.0), Vec2::new(3.0, 1.0)),
+            false,
+        );
+    }
+
+    #[test]
+    fn touching_edges_intersect() {
+        assert_matches_raster(
+            &square(Vec2::new(0.0, 0.0), Vec2::new(2.0, 2.0)),
+            &square(Vec2::new(2.0, 0.0), Vec2::new(4.0, 2.0)),
+            true,
+        );
+        let diamond = [
+            Vec2::new(2.0, 0.0),
+            Vec2::new(4.0, 2.0),
+            Vec2::new(2.0, 4.0),
+            Vec2::new(0.0, 2.0),
+        ];
+        assert_matches_raster(
+            &diamond,
+            &square(Vec2::new(3.0, 3.0), Vec2::new(5.0, 5.0)),
+            true,
+        );
+    }
+
+    #[test]
+    fn touching_corners_intersect() {
+        assert_matches_raster(
+            &square(Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0)),
+            &square(Vec2::new(1.0, 1.0), Vec2::new(2.0, 2.0)),
+            true,
+        );
+        let triangle = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(0.0, 4.0),
+        ];
+        assert_matches_raster(
+            &triangle,
+            &square(Vec2::new(2.0, 2.0), Vec2::new(3.0, 3.0)),
+            true,
+        );
+    }
+
+    #[test]
+    fn contained_polygons_intersect() {
+        let outer = square(Vec2::new(0.0, 0.0), Vec2::new(4.0, 4.0));
+        assert_matches_raster(
+            &outer,
+            &square(Vec2::new(1.0, 1.0), Vec2::new(2.0, 2.0)),
+            true,
+        );
+        let triangle = [
+            Vec2::new(1.0, 1.0),
+            Vec2::new(3.0, 1.0),
+            Vec2::new(2.0, 3.0),
+        ];
+        assert_matches_raster(&outer, &triangle, true);
+    }
+
+    #[test]
+    fn separated_along_a_diagonal_dont_intersect() {
+        // The bounding boxes overlap, only the triangle's slanted edge separates them.
+        let triangle = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(0.0, 4.0),
+        ];
+        assert_matches_raster(
+            &triangle,
+            &square(Vec2::new(3.0, 3.0), Vec2::new(4.0, 4.0)),
+            false,
+        );
+    }
+
+    #[test]
+    fn random_triangles_match_raster() {
+        let mut rng = StdRng::seed_from_u64(123);
+        // Corners on whole numbers, without degenerate triangles whose outline is just a line.
+        let mut random_triangle = || loop {
+            let [a, b, c] =
+                [(); 3].map(|_| Vec2::new(rng.gen_range(0..8) as f32, rng.gen_range(0..8) as f32));
+            if (b - a).perp_dot(c - a) != 0.0 {
+                return [a, b, c];
+            }
+        };
+        // Touching and barely overlapping pairs depend on where the raster's points fall.
+        const MIN_DEPTH: f32 = 0.25;
+
+        let mut checked = 0;
+        while checked < 200 {
+            let (triangle0, triangle1) = (random_triangle(), random_triangle());
+            if overlap_depth(&triangle0, &triangle1).abs() < MIN_DEPTH {
+                continue;
+            }
+            checked += 1;
+            assert_eq!(
+                convex_polygons_intersect(&triangle0, &triangle1),
+                rasterized_overlap(&triangle0, &triangle1),
+                "{triangle0:?} {triangle1:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn triangle_and_oriented_box_match_raster() {
+        let oriented_box = rotated_box(Vec2::new(2.0, 1.0), std::f32::consts::FRAC_PI_4);
+        let ship = [
+            Vec2::new(-0.5, -0.2),
+            Vec2::new(0.5, -0.2),
+            Vec2::new(0.0, 0.3),
+        ];
+        assert_matches_raster(&ship, &oriented_box, true);
+
+        let large_triangle = [
+            Vec2::new(-10.0, -10.0),
+            Vec2::new(10.0, -10.0),
+            Vec2::new(0.0, 10.0),
+        ];
+        assert_matches_raster(&large_triangle, &oriented_box, true);
+
+        let crossing = [
+            Vec2::new(-3.0, 0.5),
+            Vec2::new(3.0, 0.5),
+            Vec2::new(0.0, 3.0),
+        ];
+        assert_matches_raster(&crossing, &oriented_box, true);
+
+        // Within the box's bounding rectangle, but beside its rotated long edge.
+        let beside = [
+            Vec2::new(1.8, -0.2),
+            Vec2::new(2.1, -0.2),
+            Vec2::new(2.0, 0.0),
+        ];
+        assert_matches_raster(&beside, &oriented_box, false);
+    }
+
+    /// Runs collision detection for one frame on a snowball moving from `previous_position` to
+    /// `position` and a gift at the origin, and counts the collisions found.
+    fn snowball_gift_collisions(previous_position: Option<Vec2>, position: Vec2) -> usize {
+        let mut app = App::new();
+        app.add_event::<CollisionEvent>()
+            .init_resource::<PlayField>()
+            .init_resource::<CollisionSettings>()
+            .insert_resource(CollisionGrid(SpatialGrid::new(GRID_CELL_SIZE)))
+            .add_system(detect_collisions);
+
+        app.world.spawn((
+            Transform::IDENTITY,
+            Collider::new(
+                ColliderShape::OrientedBox {
+                    half_extents: Vec2::splat(20.0),
+                },
+                CollisionLayer::Gifteroid,
+            )
+            .with_mask(&[CollisionLayer::Snowball]),
+        ));
+        let mut snowball = app.world.spawn((
+            Transform::from_translation(position.extend(0.0)),
+            Collider::new(
+                ColliderShape::Circle { radius: 5.0 },
+                CollisionLayer::Snowball,
+            ),
+        ));
+        if let Some(previous_position) = previous_position {
+            snowball.insert(PreviousPosition(previous_position));
+        }
+
+        app.update();
+        let events = app.world.resource::<Events<CollisionEvent>>();
+        events.get_reader().iter(events).count()
+    }
+
+    #[test]
+    fn snowball_crossing_a_gift_in_one_step_is_detected() {
+        // Way more than the gift's size in a single frame, neither end touches the gift.
+        let (start, end) = (Vec2::new(-300.0, 10.0), Vec2::new(300.0, -10.0));
+        assert_eq!(snowball_gift_collisions(None, end), 0);
+        assert_eq!(snowball_gift_collisions(Some(start), end), 1);
+    }
+
+    #[test]
+    fn snowball_passing_a_gift_in_one_step_is_not_detected() {
+        let (start, end) = (Vec2::new(-300.0, 40.0), Vec2::new(300.0, 40.0));
+        assert_eq!(snowball_gift_collisions(Some(start), end), 0);
+    }
 }
@@ -0,0 +1,366 @@
+use bevy::{
+    input::{touch::Touch, InputSystem},
+    prelude::*,
+};
+
+use crate::{collision::CollisionSettings, gifteroids::GifteroidSettings, GameState};
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ShipControls>()
+            .init_resource::<TouchControls>()
+            .init_resource::<ControlSettings>()
+            .add_startup_system(setup_touch_controls)
+            .add_system_to_stage(CoreStage::PreUpdate, read_ship_controls.after(InputSystem))
+            .add_system(toggle_settings)
+            .add_system(update_touch_controls_display);
+    }
+}
+
+const JOYSTICK_RADIUS: f32 = 80.0;
+const JOYSTICK_KNOB_SIZE: f32 = 60.0;
+const FIRE_BUTTON_SIZE: f32 = 120.0;
+const ACTION_BUTTON_SIZE: f32 = 80.0;
+const TOUCH_BUTTON_SPACING: f32 = 20.0;
+const TOUCH_CONTROLS_MARGIN: f32 = 40.0;
+
+/// Input independent ship controls, gathered from keyboard and touch every frame.
+#[derive(Resource, Default)]
+pub struct ShipControls {
+    /// Rotation input in [-1, 1], positive values rotate counter-clockwise.
+    pub rotation: f32,
+    /// Thrust input in [0, 1].
+    pub thrust: f32,
+    pub fire_pressed: bool,
+    pub fire_just_pressed: bool,
+    pub cycle_fire_mode: bool,
+    pub activate_shield: bool,
+    pub hyperspace: bool,
+}
+
+#[derive(Resource, Default)]
+pub struct ControlSettings {
+    /// Holding fire shoots continuously at the weapon's fire rate.
+    pub autofire: bool,
+}
+
+/// Touch input state. Touch controls are shown as soon as the first touch was detected.
+#[derive(Resource, Default)]
+pub struct TouchControls {
+    pub detected: bool,
+    joystick_touch: Option<u64>,
+    fire_touch: Option<u64>,
+}
+
+impl TouchControls {
+    pub fn continue_prompt(&self) -> &'static str {
+        if self.detected {
+            "Tap to try again"
+        } else {
+            "Press Enter to try again"
+        }
+    }
+
+    pub fn continue_requested(&self, keys: &Input<KeyCode>, touches: &Touches) -> bool {
+        keys.pressed(KeyCode::NumpadEnter)
+            || keys.pressed(KeyCode::Return)
+            || (self.detected && touches.any_just_pressed())
+    }
+}
+
+#[derive(Component)]
+struct TouchControlsRoot;
+
+#[derive(Component)]
+struct JoystickKnob;
+
+fn setup_touch_controls(mut commands: Commands, asset_server: Res<AssetServer>) {
+    // Only round sprite we have, good enough for a virtual joystick.
+    let round_image = UiImage::from(asset_server.load("snowball.png"));
+
+    let base_size = JOYSTICK_RADIUS * 2.0;
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(TouchControlsRoot)
+        .with_children(|parent| {
+            parent
+                .spawn(ImageBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(base_size), Val::Px(base_size)),
+                        position_type: PositionType::Absolute,
+                        position: UiRect {
+                            left: Val::Px(TOUCH_CONTROLS_MARGIN),
+                            bottom: Val::Px(TOUCH_CONTROLS_MARGIN),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    image: round_image.clone(),
+                    background_color: BackgroundColor(Color::rgba(1.0, 1.0, 1.0, 0.2)),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn(ImageBundle {
+                            style: Style {
+                                size: Size::new(
+                                    Val::Px(JOYSTICK_KNOB_SIZE),
+                                    Val::Px(JOYSTICK_KNOB_SIZE),
+                                ),
+                                position_type: PositionType::Absolute,
+                                position: joystick_knob_position(Vec2::ZERO),
+                                ..default()
+                            },
+                            image: round_image.clone(),
+                            background_color: BackgroundColor(Color::rgba(1.0, 1.0, 1.0, 0.5)),
+                            ..default()
+                        })
+                        .insert(JoystickKnob);
+                });
+
+            parent.spawn(ImageBundle {
+                style: Style {
+                    size: Size::new(Val::Px(FIRE_BUTTON_SIZE), Val::Px(FIRE_BUTTON_SIZE)),
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        right: Val::Px(TOUCH_CONTROLS_MARGIN),
+                        bottom: Val::Px(TOUCH_CONTROLS_MARGIN),
+                        ..default()
+                    },
+                    ..default()
+                },
+                image: round_image.clone(),
+                background_color: BackgroundColor(Color::rgba(1.0, 0.3, 0.3, 0.4)),
+                ..default()
+            });
+
+            for (button, color) in ACTION_BUTTONS {
+                let center = button.center_from_bottom_right();
+                parent.spawn(ImageBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(ACTION_BUTTON_SIZE), Val::Px(ACTION_BUTTON_SIZE)),
+                        position_type: PositionType::Absolute,
+                        position: UiRect {
+                            right: Val::Px(center.x - ACTION_BUTTON_SIZE * 0.5),
+                            bottom: Val::Px(center.y - ACTION_BUTTON_SIZE * 0.5),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    image: round_image.clone(),
+                    background_color: BackgroundColor(color),
+                    ..default()
+                });
+            }
+        });
+}
+
+fn joystick_knob_position(offset: Vec2) -> UiRect {
+    let center = JOYSTICK_RADIUS - JOYSTICK_KNOB_SIZE * 0.5;
+    UiRect {
+        left: Val::Px(center + offset.x),
+        bottom: Val::Px(center + offset.y),
+        ..default()
+    }
+}
+
+/// Touch position with the origin at the bottom left of the window.
+fn touch_position(touch: &Touch, window: &Window) -> Vec2 {
+    let position = touch.position();
+    // Browsers report touches from the top of the page.
+    if cfg!(target_arch = "wasm32") {
+        Vec2::new(position.x, window.height() - position.y)
+    } else {
+        position
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ActionButton {
+    Shield,
+    Hyperspace,
+}
+
+const ACTION_BUTTONS: [(ActionButton, Color); 2] = [
+    (ActionButton::Shield, Color::rgba(0.3, 1.0, 1.0, 0.4)),
+    (ActionButton::Hyperspace, Color::rgba(0.7, 0.3, 1.0, 0.4)),
+];
+
+const FIRE_BUTTON_CENTER_FROM_BOTTOM_RIGHT: Vec2 =
+    Vec2::splat(TOUCH_CONTROLS_MARGIN + FIRE_BUTTON_SIZE * 0.5);
+
+impl ActionButton {
+    /// Action buttons are placed around the fire button.
+    fn center_from_bottom_right(&self) -> Vec2 {
+        let distance = (FIRE_BUTTON_SIZE + ACTION_BUTTON_SIZE) * 0.5 + TOUCH_BUTTON_SPACING;
+        match self {
+            ActionButton::Shield => FIRE_BUTTON_CENTER_FROM_BOTTOM_RIGHT + Vec2::new(0.0, distance),
+            ActionButton::Hyperspace => {
+                FIRE_BUTTON_CENTER_FROM_BOTTOM_RIGHT + Vec2::new(distance, 0.0)
+            }
+        }
+    }
+
+    fn is_touched(&self, touch: &Touch, window: &Window) -> bool {
+        is_round_button_touched(
+            touch,
+            window,
+            self.center_from_bottom_right(),
+            ACTION_BUTTON_SIZE,
+        )
+    }
+}
+
+fn is_fire_button_touched(touch: &Touch, window: &Window) -> bool {
+    is_round_button_touched(
+        touch,
+        window,
+        FIRE_BUTTON_CENTER_FROM_BOTTOM_RIGHT,
+        FIRE_BUTTON_SIZE,
+    )
+}
+
+fn is_round_button_touched(
+    touch: &Touch,
+    window: &Window,
+    center_from_bottom_right: Vec2,
+    size: f32,
+) -> bool {
+    let center = Vec2::new(
+        window.width() - center_from_bottom_right.x,
+        center_from_bottom_right.y,
+    );
+    touch_position(touch, window).distance(center) < size * 0.5
+}
+
+fn joystick_offset(touch: &Touch, window: &Window) -> Vec2 {
+    let center = Vec2::splat(TOUCH_CONTROLS_MARGIN + JOYSTICK_RADIUS);
+    (touch_position(touch, window) - center).clamp_length_max(JOYSTICK_RADIUS)
+}
+
+fn read_ship_controls(
+    keyboard_input: Res<Input<KeyCode>>,
+    touches: Res<Touches>,
+    windows: Res<Windows>,
+    mut controls: ResMut<ShipControls>,
+    mut touch_controls: ResMut<TouchControls>,
+    mut settings: ResMut<ControlSettings>,
+) {
+    let Some(window) = windows.get_primary() else {
+        return;
+    };
+
+    // Touches starting on the left half drive the joystick, on the right half only the buttons
+    // react to them.
+    let mut touched_action_buttons = Vec::new();
+    for touch in touches.iter_just_pressed() {
+        if !touch_controls.detected {
+            // Tapping the fire button for every shot is no fun.
+            settings.autofire = true;
+        }
+        touch_controls.detected = true;
+        if touch_position(touch, window).x < window.width() * 0.5 {
+            touch_controls.joystick_touch = Some(touch.id());
+        } else if let Some((button, _)) = ACTION_BUTTONS
+            .iter()
+            .find(|(button, _)| button.is_touched(touch, window))
+        {
+            touched_action_buttons.push(*button);
+        } else if is_fire_button_touched(touch, window) {
+            touch_controls.fire_touch = Some(touch.id());
+        }
+    }
+    let joystick_touch = touch_controls
+        .joystick_touch
+        .and_then(|id| touches.get_pressed(id));
+    let fire_touch = touch_controls
+        .fire_touch
+        .and_then(|id| touches.get_pressed(id));
+    if joystick_touch.is_none() {
+        touch_controls.joystick_touch = None;
+    }
+    if fire_touch.is_none() {
+        touch_controls.fire_touch = None;
+    }
+
+    let mut rotation = 0.0;
+    let mut thrust = 0.0;
+    if keyboard_input.pressed(KeyCode::Left) {
+        rotation += 1.0;
+    }
+    if keyboard_input.pressed(KeyCode::Right) {
+        rotation -= 1.0;
+    }
+    if keyboard_input.pressed(KeyCode::Up) {
+        thrust = 1.0;
+    }
+    if let Some(touch) = joystick_touch {
+        let offset = joystick_offset(touch, window) / JOYSTICK_RADIUS;
+        rotation -= offset.x;
+        thrust += offset.y.max(0.0);
+    }
+
+    controls.rotation = rotation.clamp(-1.0, 1.0);
+    controls.thrust = thrust.min(1.0);
+    controls.fire_pressed = keyboard_input.pressed(KeyCode::Space) || fire_touch.is_some();
+    controls.fire_just_pressed = keyboard_input.just_pressed(KeyCode::Space)
+        || fire_touch.map_or(false, |touch| touches.just_pressed(touch.id()));
+    controls.cycle_fire_mode = keyboard_input.just_pressed(KeyCode::F);
+    controls.activate_shield = keyboard_input.just_pressed(KeyCode::Down)
+        || touched_action_buttons.contains(&ActionButton::Shield);
+    controls.hyperspace = keyboard_input.just_pressed(KeyCode::H)
+        || touched_action_buttons.contains(&ActionButton::Hyperspace);
+}
+
+fn toggle_settings(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut settings: ResMut<ControlSettings>,
+    mut collision_settings: ResMut<CollisionSettings>,
+    mut gifteroid_settings: ResMut<GifteroidSettings>,
+) {
+    if keyboard_input.just_pressed(KeyCode::A) {
+        settings.autofire = !settings.autofire;
+    }
+    if keyboard_input.just_pressed(KeyCode::P) {
+        collision_settings.pixel_perfect = !collision_settings.pixel_perfect;
+    }
+    if keyboard_input.just_pressed(KeyCode::B) {
+        gifteroid_settings.elastic_collisions = !gifteroid_settings.elastic_collisions;
+    }
+}
+
+fn update_touch_controls_display(
+    touch_controls: Res<TouchControls>,
+    touches: Res<Touches>,
+    windows: Res<Windows>,
+    state: Res<State<GameState>>,
+    mut root_query: Query<&mut Visibility, With<TouchControlsRoot>>,
+    mut knob_query: Query<&mut Style, With<JoystickKnob>>,
+) {
+    let mut visibility = root_query.single_mut();
+    visibility.is_visible = touch_controls.detected && *state.current() == GameState::Game;
+    if !visibility.is_visible {
+        return;
+    }
+
+    let offset = match (
+        touch_controls
+            .joystick_touch
+            .and_then(|id| touches.get_pressed(id)),
+        windows.get_primary(),
+    ) {
+        (Some(touch), Some(window)) => joystick_offset(touch, window),
+        _ => Vec2::ZERO,
+    };
+    knob_query.single_mut().position = joystick_knob_position(offset);
+}
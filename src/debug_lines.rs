@@ -3,61 +3,25 @@
 use bevy::prelude::*;
 use bevy_prototype_debug_lines::*;
 
-use crate::gifteroids::OrientedBox;
-use crate::spaceship::SpaceShip;
+use crate::collision::Collider;
 
 pub struct DebugLinesPlugin;
 
 impl Plugin for DebugLinesPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugin(bevy_prototype_debug_lines::DebugLinesPlugin::default())
-            .add_system(draw_obb_debug_lines)
-            .add_system(draw_spaceship_debug_lines);
+            .add_system(draw_collider_debug_lines);
     }
 }
 
-fn draw_obb_debug_lines(
-    mut lines: ResMut<DebugLines>,
-    mut query: Query<(&mut Transform, &OrientedBox)>,
-) {
-    for (transform, obb) in &mut query {
-        lines.line_colored(
-            transform.translation - Vec3::from((obb.axis0 - obb.axis1, 0.0)),
-            transform.translation - Vec3::from((obb.axis0 + obb.axis1, 0.0)),
-            0.0,
-            Color::ORANGE_RED,
-        );
-        lines.line_colored(
-            transform.translation + Vec3::from((obb.axis0 - obb.axis1, 0.0)),
-            transform.translation + Vec3::from((obb.axis0 + obb.axis1, 0.0)),
-            0.0,
-            Color::ORANGE_RED,
-        );
-        lines.line_colored(
-            transform.translation - Vec3::from((obb.axis1 - obb.axis0, 0.0)),
-            transform.translation - Vec3::from((obb.axis1 + obb.axis0, 0.0)),
-            0.0,
-            Color::ORANGE_RED,
-        );
-        lines.line_colored(
-            transform.translation + Vec3::from((obb.axis1 - obb.axis0, 0.0)),
-            transform.translation + Vec3::from((obb.axis1 + obb.axis0, 0.0)),
-            0.0,
-            Color::ORANGE_RED,
-        );
-    }
-}
-
-#[cfg(feature = "debug_lines")]
-fn draw_spaceship_debug_lines(
-    mut lines: ResMut<DebugLines>,
-    query: Query<&Transform, With<SpaceShip>>,
-) {
-    for transform in &query {
-        let (tri_a, tri_b, tri_c) = SpaceShip::bounding_triangle(transform);
+fn draw_collider_debug_lines(mut lines: ResMut<DebugLines>, query: Query<(&Transform, &Collider)>) {
+    const CIRCLE_SEGMENTS: usize = 16;
 
-        lines.line_colored(tri_a.extend(0.0), tri_b.extend(0.0), 0.0, Color::ORANGE_RED);
-        lines.line_colored(tri_b.extend(0.0), tri_c.extend(0.0), 0.0, Color::ORANGE_RED);
-        lines.line_colored(tri_c.extend(0.0), tri_a.extend(0.0), 0.0, Color::ORANGE_RED);
+    for (transform, collider) in &query {
+        let outline = collider.world_shape(transform).outline(CIRCLE_SEGMENTS);
+        for (i, start) in outline.iter().enumerate() {
+            let end = outline[(i + 1) % outline.len()];
+            lines.line_colored(start.extend(0.0), end.extend(0.0), 0.0, Color::ORANGE_RED);
+        }
     }
 }
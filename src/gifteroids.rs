@@ -1,10 +1,15 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
 use rand::{rngs::StdRng, Rng, SeedableRng};
 
 use crate::{
-    collision::{line_line_test, point_in_obb},
+    collision::{layer_mask, Collider, CollisionEvent, CollisionLayer, CollisionSystem},
+    powerups::ActivePowerUps,
     spaceship::{Snowball, SpaceShip, SpaceShipDestroyedEvent, SpaceShipState},
-    DespawnOnStateEnter, GameState, MovementSpeed,
+    sprite_shapes::ColliderFromSprite,
+    wrap::{PlayField, ScreenWrap},
+    AngularVelocity, DespawnOnStateEnter, GameState, MovementSpeed,
 };
 
 pub struct GifteroidsPlugin;
@@ -12,58 +17,263 @@ pub struct GifteroidsPlugin;
 impl Plugin for GifteroidsPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<GifteroidDestroyedEvent>()
+            .init_resource::<GifteroidSettings>()
             .add_startup_system(on_load)
             .add_system_set(SystemSet::on_enter(GameState::Game).with_system(spawn_gifteroids))
             .add_system_set(
                 SystemSet::on_update(GameState::Game)
-                    .with_system(gifteroid_snowball_collision)
-                    .with_system(gifteroid_spaceship_collision)
-                    .with_system(screen_wrap_obb_entities)
+                    .with_system(gifteroid_snowball_collision.after(CollisionSystem))
+                    .with_system(gifteroid_spaceship_collision.after(CollisionSystem))
+                    .with_system(gifteroid_gifteroid_collision.after(CollisionSystem))
+                    .with_system(update_gifteroid_collision_masks.before(CollisionSystem))
+                    .with_system(home_gifteroids)
                     .with_system(check_win_condition),
             );
     }
 }
 
-#[derive(Resource)]
-pub struct GiftSprites {
-    pub gift0: Handle<Image>,
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GiftType {
+    Plain,
+    Armored,
+    Explosive,
+    Splitter,
+    Homing,
 }
 
-pub struct GifteroidDestroyedEvent {
-    pub split_in_two: bool,
+pub struct GiftTypeDefinition {
+    pub gift_type: GiftType,
+    /// Tint of the [`GiftSprite`], the only visual difference between the types.
+    pub color: Color,
+    pub score: u32,
+    /// Snowball hits it takes to destroy the gift.
+    pub hits: u32,
+    /// Fragments on top of the size tier's, fragments are of the same type.
+    pub extra_fragments: u32,
+    /// Destroys all other gifts in this radius when destroyed, zero for no explosion.
+    pub explosion_radius: f32,
+    /// How fast the gift turns towards the ship, in fractions of its speed per second.
+    pub homing_turn_rate: f32,
+    /// Relative chance of being picked for a new gift.
+    pub spawn_weight: u32,
 }
 
+pub const GIFT_TYPES: [GiftTypeDefinition; 5] = [
+    GiftTypeDefinition {
+        gift_type: GiftType::Plain,
+        color: Color::WHITE,
+        score: 10,
+        hits: 1,
+        extra_fragments: 0,
+        explosion_radius: 0.0,
+        homing_turn_rate: 0.0,
+        spawn_weight: 6,
+    },
+    GiftTypeDefinition {
+        gift_type: GiftType::Armored,
+        color: Color::GRAY,
+        score: 30,
+        hits: 3,
+        extra_fragments: 0,
+        explosion_radius: 0.0,
+        homing_turn_rate: 0.0,
+        spawn_weight: 2,
+    },
+    GiftTypeDefinition {
+        gift_type: GiftType::Explosive,
+        color: Color::ORANGE_RED,
+        score: 20,
+        hits: 1,
+        extra_fragments: 0,
+        explosion_radius: 150.0,
+        homing_turn_rate: 0.0,
+        spawn_weight: 1,
+    },
+    GiftTypeDefinition {
+        gift_type: GiftType::Splitter,
+        color: Color::LIME_GREEN,
+        score: 15,
+        hits: 1,
+        extra_fragments: 1,
+        explosion_radius: 0.0,
+        homing_turn_rate: 0.0,
+        spawn_weight: 2,
+    },
+    GiftTypeDefinition {
+        gift_type: GiftType::Homing,
+        color: Color::FUCHSIA,
+        score: 25,
+        hits: 1,
+        extra_fragments: 0,
+        explosion_radius: 0.0,
+        homing_turn_rate: 0.5,
+        spawn_weight: 1,
+    },
+];
+
+impl GiftType {
+    pub fn definition(&self) -> &'static GiftTypeDefinition {
+        &GIFT_TYPES[self.index()]
+    }
+
+    fn index(&self) -> usize {
+        GIFT_TYPES
+            .iter()
+            .position(|definition| definition.gift_type == *self)
+            .unwrap()
+    }
+
+    /// Picks a type according to the spawn weights, so waves mix types.
+    pub fn random(rng: &mut StdRng) -> GiftType {
+        let total_weight: u32 = GIFT_TYPES.iter().map(|d| d.spawn_weight).sum();
+        let mut pick = rng.gen_range(0..total_weight);
+        for definition in &GIFT_TYPES {
+            if pick < definition.spawn_weight {
+                return definition.gift_type;
+            }
+            pick -= definition.spawn_weight;
+        }
+        unreachable!()
+    }
+}
+
+/// Shared by all gift types, they are only told apart by their tint.
+#[derive(Resource)]
+pub struct GiftSprite(pub Handle<Image>);
+
 #[derive(Component)]
-pub struct OrientedBox {
-    // Half axis from center. axis are in a right angle
-    pub axis0: Vec2,
-    pub axis1: Vec2,
+pub struct Gift {
+    pub gift_type: GiftType,
+    hits_left: u32,
+    spawn_time: Duration,
+}
+
+#[derive(Resource, Default)]
+pub struct GifteroidSettings {
+    /// Gifteroids bounce off each other instead of passing through.
+    pub elastic_collisions: bool,
+}
+
+/// Sent for every destroyed gifteroid, including the ones caught in an explosion.
+pub struct GifteroidDestroyedEvent {
+    /// Already despawned when the event is read.
+    pub entity: Entity,
+    pub position: Vec2,
+    pub size: GifteroidSize,
+    pub gift_type: GiftType,
+    /// The snowball that hit it, or the gifteroid whose explosion it was caught in.
+    pub destroyed_by: Entity,
+    /// Whether it broke into smaller gifteroids.
+    #[allow(dead_code)] // None of the current listeners treat split gifteroids differently.
+    pub split: bool,
+    /// How long the gifteroid was around.
+    pub age: Duration,
+    /// How far the snowball flew, none if it was caught in an explosion.
+    pub shot_distance: Option<f32>,
 }
 
 #[derive(Bundle)]
 struct GifteroidBundle {
     size: GifteroidSize,
+    gift: Gift,
     #[bundle]
     sprite: SpriteBundle,
     movement: MovementSpeed,
-    obb: OrientedBox,
+    angular_velocity: AngularVelocity,
+    collider: ColliderFromSprite,
+    wrap: ScreenWrap,
     despawner: DespawnOnStateEnter,
 }
 
-#[derive(Component, Clone, Copy)]
-pub enum GifteroidSize {
-    Large = 0,
-    Medium = 1,
-    Small = 2,
+pub struct SizeTier {
+    pub name: &'static str,
+    pub scale: f32,
+    /// Speed of newly spawned gifts.
+    pub speed: f32,
+    /// Gifts spin up to this fast, in radians per second.
+    pub max_spin: f32,
+    /// Number of gifts of the next tier it splits into, the last tier never splits.
+    pub fragments: u32,
+    /// Speed fragments fly apart with, perpendicular to the snowball's direction.
+    pub spread_speed: f32,
+    /// Fraction of the destroyed gift's velocity its fragments keep.
+    pub inherited_velocity: f32,
+    /// Smaller gifts are harder to hit and worth more.
+    pub score_multiplier: u32,
+}
+
+/// From the largest gifts to the smallest.
+pub const SIZE_TIERS: [SizeTier; 3] = [
+    SizeTier {
+        name: "Large",
+        scale: 1.0,
+        speed: 60.0,
+        max_spin: 0.6,
+        fragments: 2,
+        spread_speed: 90.0,
+        inherited_velocity: 1.0,
+        score_multiplier: 1,
+    },
+    SizeTier {
+        name: "Medium",
+        scale: 0.5,
+        speed: 120.0,
+        max_spin: 1.2,
+        fragments: 2,
+        spread_speed: 140.0,
+        inherited_velocity: 1.0,
+        score_multiplier: 2,
+    },
+    SizeTier {
+        name: "Small",
+        scale: 0.25,
+        speed: 180.0,
+        max_spin: 1.8,
+        fragments: 0,
+        spread_speed: 0.0,
+        inherited_velocity: 0.0,
+        score_multiplier: 4,
+    },
+];
+
+/// Index into [`SIZE_TIERS`].
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct GifteroidSize(pub usize);
+
+impl GifteroidSize {
+    pub const LARGEST: GifteroidSize = GifteroidSize(0);
+
+    pub fn tier(&self) -> &'static SizeTier {
+        &SIZE_TIERS[self.0]
+    }
+
+    /// Size of the fragments, none for the smallest size.
+    fn smaller(&self) -> Option<GifteroidSize> {
+        (self.0 + 1 < SIZE_TIERS.len()).then_some(GifteroidSize(self.0 + 1))
+    }
+
+    /// Proportional to the sprite's area, the largest gifteroid weighs 1.
+    fn mass(&self) -> f32 {
+        self.tier().scale.powi(2)
+    }
+
+    /// Movement in a random direction at the tier's speed.
+    pub fn random_movement(&self, rng: &mut StdRng) -> Vec2 {
+        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        Vec2::new(angle.cos(), angle.sin()) * self.tier().speed
+    }
 }
 
 fn on_load(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.insert_resource(GiftSprites {
-        gift0: asset_server.load("gift.png"),
-    });
+    commands.insert_resource(GiftSprite(asset_server.load("gift.png")));
 }
 
-fn spawn_gifteroids(windows: Res<Windows>, sprites: Res<GiftSprites>, mut commands: Commands) {
+fn spawn_gifteroids(
+    windows: Res<Windows>,
+    sprite: Res<GiftSprite>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
     let window = windows.get_primary().unwrap();
     let screen_size = Vec2 {
         x: window.width(),
@@ -84,46 +294,63 @@ fn spawn_gifteroids(windows: Res<Windows>, sprites: Res<GiftSprites>, mut comman
                 * if rng.gen::<bool>() { -1.0 } else { 1.0 },
         };
 
+        let gift_type = GiftType::random(&mut rng);
+        let movement = GifteroidSize::LARGEST.random_movement(&mut rng);
         spawn_gifteroid(
             &mut commands,
-            sprites.gift0.clone(),
-            position,
+            sprite.0.clone(),
+            time.elapsed(),
+            GifteroidSpawn {
+                position,
+                movement,
+                size: GifteroidSize::LARGEST,
+                gift_type,
+            },
             &mut rng,
-            GifteroidSize::Large,
         )
     }
 }
 
+/// Where and what kind of gifteroid to spawn.
+pub struct GifteroidSpawn {
+    pub position: Vec2,
+    pub movement: Vec2,
+    pub size: GifteroidSize,
+    pub gift_type: GiftType,
+}
+
 pub fn spawn_gifteroid(
     commands: &mut Commands,
     texture: Handle<Image>,
-    position: Vec2,
+    spawn_time: Duration,
+    spawn: GifteroidSpawn,
     rand: &mut StdRng,
-    size: GifteroidSize,
 ) {
-    const GIFTEROIDS_BASE_SPEED: f32 = 60.0;
-
-    // manual measurement from gift.png
-    const GIFTSPRITE_HALF_EXTENT_X: f32 = 46.0;
-    const GIFTSPRITE_HALF_EXTENT_Y: f32 = 64.0;
-
-    let movement_angle = rand.gen_range(0.0..std::f32::consts::TAU);
+    let GifteroidSpawn {
+        position,
+        movement,
+        size,
+        gift_type,
+    } = spawn;
+    let tier = size.tier();
     let sprite_angle = rand.gen_range(0.0..std::f32::consts::TAU);
+    let spin = tier.max_spin * rand.gen_range(-1.0..1.0);
+    let scale = tier.scale;
 
-    let speed = GIFTEROIDS_BASE_SPEED * (size as i32 + 1) as f32; // TODO: Vary this?
-    let scale = 0.5_f32.powi(size as i32);
-    let movement = Vec2::new(movement_angle.cos(), movement_angle.sin()) * speed;
-
-    let sprite_x_dir = Vec2::new(sprite_angle.cos(), sprite_angle.sin());
-    let obb = OrientedBox {
-        axis0: Vec2::new(GIFTSPRITE_HALF_EXTENT_X * scale, 0.0).rotate(sprite_x_dir),
-        axis1: Vec2::new(0.0, GIFTSPRITE_HALF_EXTENT_Y * scale).rotate(sprite_x_dir),
-    };
-
+    let definition = gift_type.definition();
     commands.spawn(GifteroidBundle {
         size,
+        gift: Gift {
+            gift_type,
+            hits_left: definition.hits,
+            spawn_time,
+        },
         sprite: SpriteBundle {
             texture,
+            sprite: Sprite {
+                color: definition.color,
+                ..default()
+            },
             transform: Transform {
                 translation: position.extend(0.0),
                 rotation: Quat::from_rotation_z(sprite_angle),
@@ -132,132 +359,290 @@ pub fn spawn_gifteroid(
             ..default()
         },
         movement: MovementSpeed(movement),
-        obb,
+        angular_velocity: AngularVelocity(spin),
+        // Collisions with other gifteroids are masked in while elastic collisions are enabled.
+        collider: ColliderFromSprite::new(CollisionLayer::Gifteroid, &[]),
+        // Radius is set once the collider is known.
+        wrap: ScreenWrap { radius: 0.0 },
         despawner: DespawnOnStateEnter(GameState::Game),
     });
 }
 
-fn screen_wrap_obb_entities(
-    camera_query: Query<&OrthographicProjection, With<Camera2d>>,
-    mut query: Query<(&mut Transform, &OrientedBox)>,
-) {
-    let camera = camera_query.single();
-
-    for (mut transform, obb) in &mut query {
-        let axis0_abs = obb.axis0.abs();
-        let axis1_abs = obb.axis1.abs();
-        let max_axis_step = axis0_abs + axis1_abs;
-        let min = transform.translation.truncate() - max_axis_step;
-        let max = transform.translation.truncate() + max_axis_step;
-        let size = (max - min).abs();
-
-        if max.y < camera.bottom {
-            transform.translation.y = camera.top + size.y * 0.5 - 0.1;
-        } else if min.y > camera.top {
-            transform.translation.y = camera.bottom - size.y * 0.5 + 0.1;
-        }
-        if max.x < camera.left {
-            transform.translation.x = camera.right + size.x * 0.5 - 0.1;
-        } else if min.x > camera.right {
-            transform.translation.x = camera.left - size.x * 0.5 + 0.1;
-        }
-    }
-}
+type GifteroidComponents<'a> = (
+    Entity,
+    &'a Transform,
+    &'a MovementSpeed,
+    &'a GifteroidSize,
+    &'a mut Gift,
+    &'a Handle<Image>,
+);
 
 fn gifteroid_snowball_collision(
     mut commands: Commands,
-    sprites: Res<GiftSprites>,
-    query_gifteroids: Query<(Entity, &Transform, &OrientedBox, &GifteroidSize)>,
-    query_snowballs: Query<(Entity, &Transform), With<Snowball>>,
+    time: Res<Time>,
+    play_field: Res<PlayField>,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut query_gifteroids: Query<GifteroidComponents>,
+    mut query_snowballs: Query<(&mut Snowball, &MovementSpeed)>,
     mut destroyed_events: EventWriter<GifteroidDestroyedEvent>,
 ) {
-    for (entity_gifteroid, transform_gifteroid, obb, size) in &query_gifteroids {
-        let position_gifteroid = transform_gifteroid.translation.truncate();
-        for (entity_snowball, transform_snowball) in &query_snowballs {
-            // snowballs have a radius, but we ignore it here since they move fast enough
-            if point_in_obb(
-                obb,
-                transform_snowball.translation.truncate(),
-                position_gifteroid,
-            ) {
-                commands.entity(entity_gifteroid).despawn();
-                commands.entity(entity_snowball).despawn();
-
-                destroyed_events.send(GifteroidDestroyedEvent {
-                    split_in_two: matches!(size, GifteroidSize::Small),
-                });
-
-                let new_size = match size {
-                    GifteroidSize::Large => GifteroidSize::Medium,
-                    GifteroidSize::Medium => GifteroidSize::Small,
-                    GifteroidSize::Small => continue,
-                };
-
-                let seed = query_gifteroids.iter().len();
-                let mut rng = StdRng::seed_from_u64(seed as u64);
-
-                for _ in 0..2 {
-                    spawn_gifteroid(
-                        &mut commands,
-                        sprites.gift0.clone(),
-                        position_gifteroid,
-                        &mut rng,
-                        new_size,
-                    )
+    let mut destroyed = Vec::new();
+    for event in collision_events.iter() {
+        let Some((entity_gifteroid, entity_snowball)) =
+            event.sorted(|e| query_gifteroids.contains(e))
+        else {
+            continue;
+        };
+        let Ok((mut snowball, snowball_movement)) = query_snowballs.get_mut(entity_snowball) else {
+            continue;
+        };
+        if snowball.hits_left == 0 || destroyed.contains(&entity_gifteroid) {
+            continue;
+        }
+
+        snowball.hits_left -= 1;
+
+        let (_, _, _, _, mut gift, _) = query_gifteroids.get_mut(entity_gifteroid).unwrap();
+        gift.hits_left -= 1;
+        if gift.hits_left > 0 {
+            continue;
+        }
+
+        // Explosions destroy neighbors regardless of their armor, and may set off other explosions.
+        let shot_distance =
+            snowball_movement.0.length() * (time.elapsed() - snowball.spawn_time).as_secs_f32();
+
+        let mut to_destroy = vec![(
+            entity_gifteroid,
+            snowball_movement.0.normalize_or_zero(),
+            entity_snowball,
+        )];
+        while let Some((entity, impact_direction, destroyed_by)) = to_destroy.pop() {
+            if destroyed.contains(&entity) {
+                continue;
+            }
+            destroyed.push(entity);
+
+            let (_, transform_gifteroid, movement_gifteroid, size, gift, texture) =
+                query_gifteroids.get(entity).unwrap();
+            let position_gifteroid = transform_gifteroid.translation.truncate();
+            let definition = gift.gift_type.definition();
+            let fragments = match size.smaller() {
+                Some(_) => size.tier().fragments + definition.extra_fragments,
+                None => 0,
+            };
+            commands.entity(entity).despawn();
+
+            destroyed_events.send(GifteroidDestroyedEvent {
+                entity,
+                position: position_gifteroid,
+                size: *size,
+                gift_type: gift.gift_type,
+                destroyed_by,
+                split: fragments > 0,
+                age: time.elapsed() - gift.spawn_time,
+                shot_distance: (destroyed_by == entity_snowball).then_some(shot_distance),
+            });
+
+            if definition.explosion_radius > 0.0 {
+                for (other, transform_other, _, _, _, _) in &query_gifteroids {
+                    let delta = play_field
+                        .wrapped_delta(position_gifteroid, transform_other.translation.truncate());
+                    if delta.length() < definition.explosion_radius && !destroyed.contains(&other) {
+                        to_destroy.push((other, delta.normalize_or_zero(), entity));
+                    }
                 }
             }
+
+            let Some(new_size) = size.smaller() else {
+                continue;
+            };
+
+            let seed = query_gifteroids.iter().len() + destroyed.len();
+            let mut rng = StdRng::seed_from_u64(seed as u64);
+
+            for movement in fragment_velocities(
+                size.tier(),
+                movement_gifteroid.0,
+                impact_direction,
+                fragments,
+            ) {
+                // Fragments keep their parent's sprite.
+                spawn_gifteroid(
+                    &mut commands,
+                    texture.clone(),
+                    time.elapsed(),
+                    GifteroidSpawn {
+                        position: position_gifteroid,
+                        movement,
+                        size: new_size,
+                        gift_type: gift.gift_type,
+                    },
+                    &mut rng,
+                )
+            }
         }
     }
 }
 
+/// Fragments keep the parent's velocity and fly apart across the impact line, evenly from one side
+/// to the other.
+fn fragment_velocities(
+    tier: &SizeTier,
+    parent_velocity: Vec2,
+    impact_direction: Vec2,
+    fragments: u32,
+) -> impl Iterator<Item = Vec2> {
+    let inherited = parent_velocity * tier.inherited_velocity;
+    let spread = impact_direction.perp() * tier.spread_speed;
+    (0..fragments).map(move |i| {
+        let side = if fragments > 1 {
+            i as f32 / (fragments - 1) as f32 * 2.0 - 1.0
+        } else {
+            0.0
+        };
+        inherited + spread * side
+    })
+}
+
 fn gifteroid_spaceship_collision(
-    query_gifteroids: Query<(&Transform, &OrientedBox), With<GifteroidSize>>,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut query_gifteroids: Query<(&Transform, &mut MovementSpeed), With<GifteroidSize>>,
     mut query_spaceship: Query<(&Transform, &mut SpaceShip)>,
     mut destroyed_events: EventWriter<SpaceShipDestroyedEvent>,
+    active_power_ups: Res<ActivePowerUps>,
 ) {
-    if query_spaceship.get_single().is_err() {
-        return;
+    for event in collision_events.iter() {
+        let Some((entity_spaceship, entity_gifteroid)) =
+            event.sorted(|e| query_spaceship.contains(e))
+        else {
+            continue;
+        };
+        let Ok((transform_gifteroid, mut movement)) = query_gifteroids.get_mut(entity_gifteroid)
+        else {
+            continue;
+        };
+        let (spaceship_transform, mut spaceship) =
+            query_spaceship.get_mut(entity_spaceship).unwrap();
+        if matches!(
+            spaceship.state,
+            SpaceShipState::Invincible(_) | SpaceShipState::Destroyed
+        ) {
+            // Keep reading, so the remaining events of this frame aren't seen again next frame.
+            continue;
+        }
+
+        if spaceship.is_shielded(&active_power_ups) {
+            // Bounce the gifteroid away from the ship, keeping its speed.
+            let away = (transform_gifteroid.translation - spaceship_transform.translation)
+                .truncate()
+                .normalize_or_zero();
+            movement.0 = away * movement.0.length();
+            spaceship.break_shield();
+            continue;
+        }
+
+        spaceship.state = SpaceShipState::Destroyed;
+        destroyed_events.send(SpaceShipDestroyedEvent {
+            lives_left_before_destroy: spaceship.lives_left,
+            position: spaceship_transform.translation.truncate(),
+        });
+    }
+}
+
+/// Gifteroids are only tested against each other while elastic collisions are enabled,
+/// the setting can be toggled at any time.
+fn update_gifteroid_collision_masks(
+    settings: Res<GifteroidSettings>,
+    mut query: Query<&mut Collider, With<GifteroidSize>>,
+) {
+    let mask = if settings.elastic_collisions {
+        layer_mask(&[CollisionLayer::Gifteroid])
+    } else {
+        0
+    };
+    for mut collider in &mut query {
+        if collider.mask != mask {
+            collider.mask = mask;
+        }
     }
-    let (spaceship_transform, mut spaceship) = query_spaceship.single_mut();
-    if matches!(
-        spaceship.state,
-        SpaceShipState::Invincible(_) | SpaceShipState::Destroyed
-    ) {
+}
+
+fn gifteroid_gifteroid_collision(
+    settings: Res<GifteroidSettings>,
+    play_field: Res<PlayField>,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut query: Query<(
+        &Transform,
+        &GifteroidSize,
+        &mut MovementSpeed,
+        &mut AngularVelocity,
+    )>,
+) {
+    // How much spin a glancing impact adds, in radians per unit of tangential speed.
+    const IMPACT_SPIN: f32 = 0.02;
+
+    if !settings.elastic_collisions {
         return;
     }
-    let (tri_a, tri_b, tri_c) = SpaceShip::bounding_triangle(spaceship_transform);
-
-    // Detect collision by checking line collisions. Not perfect, but good enough and easy to implement
-    // outer lines of spaceship
-    for (transform_gifteroid, obb) in &query_gifteroids {
-        let position_gifteroid = transform_gifteroid.translation.truncate();
-
-        // outer lines of gifteroid
-        let top_right = position_gifteroid + obb.axis0 + obb.axis1;
-        let top_left = position_gifteroid - obb.axis0 + obb.axis1;
-        let bottom_left = position_gifteroid - obb.axis0 - obb.axis1;
-        let bottom_right = position_gifteroid + obb.axis0 - obb.axis1;
-
-        if line_line_test(tri_a, tri_b, top_right, top_left)
-            || line_line_test(tri_a, tri_b, top_left, bottom_left)
-            || line_line_test(tri_a, tri_b, bottom_left, bottom_right)
-            || line_line_test(tri_a, tri_b, bottom_right, top_right)
-            || line_line_test(tri_b, tri_c, top_right, top_left)
-            || line_line_test(tri_b, tri_c, top_left, bottom_left)
-            || line_line_test(tri_b, tri_c, bottom_left, bottom_right)
-            || line_line_test(tri_b, tri_c, bottom_right, top_right)
-            || line_line_test(tri_c, tri_a, top_right, top_left)
-            || line_line_test(tri_c, tri_a, top_left, bottom_left)
-            || line_line_test(tri_c, tri_a, bottom_left, bottom_right)
-            || line_line_test(tri_c, tri_a, bottom_right, top_right)
-        {
-            spaceship.state = SpaceShipState::Destroyed;
-            destroyed_events.send(SpaceShipDestroyedEvent {
-                lives_left_before_destroy: spaceship.lives_left,
-            });
 
-            break;
+    for event in collision_events.iter() {
+        let Ok([a, b]) = query.get_many_mut([event.a, event.b]) else {
+            continue;
+        };
+        let (transform_a, size_a, mut movement_a, mut spin_a) = a;
+        let (transform_b, size_b, mut movement_b, mut spin_b) = b;
+
+        // Treat the gifteroids as spheres for the response, the overlap test was precise already.
+        let normal = play_field
+            .wrapped_delta(
+                transform_a.translation.truncate(),
+                transform_b.translation.truncate(),
+            )
+            .normalize_or_zero();
+        let relative_velocity = movement_b.0 - movement_a.0;
+        let approach_speed = relative_velocity.dot(normal);
+        if approach_speed >= 0.0 {
+            // Already moving apart, e.g. after bouncing last frame.
+            continue;
         }
+
+        let (mass_a, mass_b) = (size_a.mass(), size_b.mass());
+        let impulse = -2.0 * approach_speed / (1.0 / mass_a + 1.0 / mass_b);
+        movement_a.0 -= normal * (impulse / mass_a);
+        movement_b.0 += normal * (impulse / mass_b);
+
+        // Rubbing along each other spins both in the same direction, lighter ones more.
+        let spin = normal.perp_dot(relative_velocity) * IMPACT_SPIN;
+        spin_a.0 += spin * mass_b / (mass_a + mass_b);
+        spin_b.0 += spin * mass_a / (mass_a + mass_b);
+    }
+}
+
+fn home_gifteroids(
+    time: Res<Time>,
+    play_field: Res<PlayField>,
+    query_spaceship: Query<&Transform, With<SpaceShip>>,
+    mut query: Query<(&Transform, &Gift, &mut MovementSpeed)>,
+) {
+    let Ok(spaceship_transform) = query_spaceship.get_single() else {
+        return;
+    };
+    let position_spaceship = spaceship_transform.translation.truncate();
+
+    for (transform, gift, mut movement) in &mut query {
+        let turn_rate = gift.gift_type.definition().homing_turn_rate;
+        if turn_rate <= 0.0 {
+            continue;
+        }
+        // Only turn, so homing gifts don't get faster than their siblings.
+        let to_spaceship = play_field
+            .wrapped_delta(transform.translation.truncate(), position_spaceship)
+            .normalize_or_zero();
+        let speed = movement.0.length();
+        movement.0 = (movement.0 + to_spaceship * (speed * turn_rate * time.delta_seconds()))
+            .normalize_or_zero()
+            * speed;
     }
 }
 
@@ -269,3 +654,26 @@ fn check_win_condition(
         state.set(GameState::Highscore).unwrap();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fragments_are_symmetric_about_parent_velocity() {
+        let parent_velocity = Vec2::new(40.0, -25.0);
+        let impact_direction = Vec2::new(0.6, 0.8);
+        for size in [GifteroidSize(0), GifteroidSize(1)] {
+            let tier = size.tier();
+            let inherited = parent_velocity * tier.inherited_velocity;
+            let velocities =
+                fragment_velocities(tier, parent_velocity, impact_direction, 2).collect::<Vec<_>>();
+            assert_eq!(velocities.len(), 2);
+
+            let (left, right) = (velocities[0] - inherited, velocities[1] - inherited);
+            assert!((left + right).length() < 1e-3);
+            assert!(left.dot(impact_direction).abs() < 1e-3);
+            assert!((left.length() - tier.spread_speed).abs() < 1e-3);
+        }
+    }
+}
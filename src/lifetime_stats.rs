@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use tinyjson::JsonValue;
+
+use crate::{
+    score::{RoundStats, Score},
+    storage, GameState,
+};
+
+pub struct LifetimeStatsPlugin;
+
+impl Plugin for LifetimeStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LifetimeStats::load())
+            .add_system_set(SystemSet::on_enter(GameState::GameOver).with_system(record_round))
+            .add_system_set(SystemSet::on_enter(GameState::Highscore).with_system(record_round))
+            .add_system_set(
+                SystemSet::on_update(GameState::LifetimeStats).with_system(export_on_key),
+            );
+    }
+}
+
+const STORAGE_KEY: &str = "lifetime_stats";
+const CSV_HEADER: &str =
+    "round,won,score,seconds,gifts_destroyed,santas_downed,lives_lost,shots_fired,hits,accuracy";
+
+struct RoundRecord {
+    won: bool,
+    score: u32,
+    seconds: f32,
+    gifts_destroyed: u32,
+    santas_downed: u32,
+    lives_lost: u32,
+    shots_fired: u32,
+    hits: u32,
+}
+
+impl RoundRecord {
+    fn accuracy(&self) -> Option<f32> {
+        (self.shots_fired > 0)
+            .then(|| self.hits.min(self.shots_fired) as f32 / self.shots_fired as f32)
+    }
+
+    fn to_json(&self) -> JsonValue {
+        JsonValue::from(HashMap::from([
+            ("won".to_string(), JsonValue::from(self.won)),
+            ("score".to_string(), JsonValue::from(self.score as f64)),
+            ("seconds".to_string(), JsonValue::from(self.seconds as f64)),
+            (
+                "gifts_destroyed".to_string(),
+                JsonValue::from(self.gifts_destroyed as f64),
+            ),
+            (
+                "santas_downed".to_string(),
+                JsonValue::from(self.santas_downed as f64),
+            ),
+            (
+                "lives_lost".to_string(),
+                JsonValue::from(self.lives_lost as f64),
+            ),
+            (
+                "shots_fired".to_string(),
+                JsonValue::from(self.shots_fired as f64),
+            ),
+            ("hits".to_string(), JsonValue::from(self.hits as f64)),
+        ]))
+    }
+
+    fn from_json(json: &JsonValue) -> Option<Self> {
+        let object: &HashMap<String, JsonValue> = json.get()?;
+        let number = |key: &str| object.get(key)?.get::<f64>().copied();
+        Some(Self {
+            won: *object.get("won")?.get::<bool>()?,
+            score: number("score")? as u32,
+            seconds: number("seconds")? as f32,
+            gifts_destroyed: number("gifts_destroyed")? as u32,
+            santas_downed: number("santas_downed")? as u32,
+            lives_lost: number("lives_lost")? as u32,
+            shots_fired: number("shots_fired")? as u32,
+            hits: number("hits")? as u32,
+        })
+    }
+}
+
+/// Every round played on this device, oldest first.
+#[derive(Resource, Default)]
+pub struct LifetimeStats {
+    rounds: Vec<RoundRecord>,
+}
+
+impl LifetimeStats {
+    fn load() -> Self {
+        let Some(contents) = storage::load(STORAGE_KEY) else {
+            return Self::default();
+        };
+        match Self::from_json(&contents) {
+            Some(stats) => stats,
+            None => {
+                warn!("Ignoring malformed lifetime stats");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn rounds_played(&self) -> usize {
+        self.rounds.len()
+    }
+
+    pub fn wins(&self) -> usize {
+        self.rounds.iter().filter(|round| round.won).count()
+    }
+
+    pub fn total_gifts(&self) -> u32 {
+        self.rounds.iter().map(|round| round.gifts_destroyed).sum()
+    }
+
+    /// Fastest win in seconds.
+    pub fn best_time(&self) -> Option<f32> {
+        self.rounds
+            .iter()
+            .filter(|round| round.won)
+            .map(|round| round.seconds)
+            .min_by(f32::total_cmp)
+    }
+
+    /// Fraction of all snowballs ever thrown that hit something.
+    pub fn accuracy(&self) -> Option<f32> {
+        let shots = self
+            .rounds
+            .iter()
+            .map(|round| round.shots_fired)
+            .sum::<u32>();
+        let hits = self.rounds.iter().map(|round| round.hits).sum::<u32>();
+        (shots > 0).then(|| hits.min(shots) as f32 / shots as f32)
+    }
+
+    /// Accuracy of the last rounds, oldest first, rounds without shots are left out.
+    pub fn recent_accuracy(&self, rounds: usize) -> Vec<f32> {
+        let start = self.rounds.len().saturating_sub(rounds);
+        self.rounds[start..]
+            .iter()
+            .filter_map(RoundRecord::accuracy)
+            .collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut summary = HashMap::from([
+            (
+                "rounds_played".to_string(),
+                JsonValue::from(self.rounds_played() as f64),
+            ),
+            ("wins".to_string(), JsonValue::from(self.wins() as f64)),
+            (
+                "total_gifts".to_string(),
+                JsonValue::from(self.total_gifts() as f64),
+            ),
+        ]);
+        if let Some(best_time) = self.best_time() {
+            summary.insert("best_time".to_string(), JsonValue::from(best_time as f64));
+        }
+        if let Some(accuracy) = self.accuracy() {
+            summary.insert("accuracy".to_string(), JsonValue::from(accuracy as f64));
+        }
+
+        JsonValue::from(HashMap::from([
+            ("summary".to_string(), JsonValue::from(summary)),
+            (
+                "rounds".to_string(),
+                JsonValue::from(
+                    self.rounds
+                        .iter()
+                        .map(RoundRecord::to_json)
+                        .collect::<Vec<_>>(),
+                ),
+            ),
+        ]))
+        .stringify()
+        .unwrap()
+    }
+
+    /// The summary is derived from the rounds, so only those are read back.
+    fn from_json(contents: &str) -> Option<Self> {
+        let json = contents.parse::<JsonValue>().ok()?;
+        let object: &HashMap<String, JsonValue> = json.get()?;
+        let rounds: &Vec<JsonValue> = object.get("rounds")?.get()?;
+        Some(Self {
+            rounds: rounds
+                .iter()
+                .map(RoundRecord::from_json)
+                .collect::<Option<_>>()?,
+        })
+    }
+
+    /// One line per round.
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("{CSV_HEADER}\n");
+        for (index, round) in self.rounds.iter().enumerate() {
+            csv += &format!(
+                "{},{},{},{:.1},{},{},{},{},{},{}\n",
+                index + 1,
+                round.won,
+                round.score,
+                round.seconds,
+                round.gifts_destroyed,
+                round.santas_downed,
+                round.lives_lost,
+                round.shots_fired,
+                round.hits,
+                round
+                    .accuracy()
+                    .map_or(String::new(), |accuracy| format!("{accuracy:.3}")),
+            );
+        }
+        csv
+    }
+}
+
+fn record_round(
+    state: Res<State<GameState>>,
+    score: Res<Score>,
+    round_stats: Res<RoundStats>,
+    mut lifetime_stats: ResMut<LifetimeStats>,
+) {
+    lifetime_stats.rounds.push(RoundRecord {
+        won: *state.current() == GameState::Highscore,
+        score: score.0,
+        seconds: round_stats.seconds,
+        gifts_destroyed: round_stats.gifts_destroyed.iter().sum(),
+        santas_downed: round_stats.santas_downed,
+        lives_lost: round_stats.lives_lost,
+        shots_fired: round_stats.shots_fired,
+        hits: round_stats.hits,
+    });
+    storage::save(STORAGE_KEY, &lifetime_stats.to_json());
+}
+
+fn export_on_key(keys: Res<Input<KeyCode>>, lifetime_stats: Res<LifetimeStats>) {
+    if keys.just_pressed(KeyCode::J) {
+        storage::export(
+            "gifteroids_stats.json",
+            "application/json",
+            &lifetime_stats.to_json(),
+        );
+    }
+    if keys.just_pressed(KeyCode::C) {
+        storage::export("gifteroids_stats.csv", "text/csv", &lifetime_stats.to_csv());
+    }
+}
+
+/// Handles `--export-stats <file>`, writing CSV for `.csv` files and JSON otherwise.
+/// Returns whether the game should exit instead of starting.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn export_from_command_line() -> bool {
+    let mut args = std::env::args().skip_while(|arg| arg != "--export-stats");
+    if args.next().is_none() {
+        return false;
+    }
+    let Some(file_name) = args.next() else {
+        eprintln!("Usage: gifteroids --export-stats <file.csv|file.json>");
+        return true;
+    };
+
+    let stats = LifetimeStats::load();
+    let contents = if file_name.ends_with(".csv") {
+        stats.to_csv()
+    } else {
+        stats.to_json()
+    };
+    match std::fs::write(&file_name, contents) {
+        Ok(()) => println!("Exported lifetime stats to {file_name}"),
+        Err(err) => eprintln!("Failed to export lifetime stats to {file_name}: {err}"),
+    }
+    true
+}
@@ -1,19 +1,28 @@
 use bevy::prelude::*;
 
+mod achievements;
 mod collision;
+mod controls;
 mod debug_lines;
 mod gifteroids;
+mod lifetime_stats;
+mod powerups;
 mod santa;
 mod score;
 mod spaceship;
+mod spatial_grid;
+mod sprite_shapes;
+mod storage;
 mod ui;
 mod web_request;
+mod wrap;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum GameState {
     Game,
     GameOver,
     Highscore,
+    LifetimeStats,
 
     // Not used for states, but useful for DespawnOnStateEnter
     Any,
@@ -22,22 +31,47 @@ pub enum GameState {
 #[derive(Component)]
 pub struct DespawnOnStateEnter(GameState);
 
+/// Systems that move objects around, collision checks should run after them.
+#[derive(SystemLabel)]
+pub struct MovementSystem;
+
 fn main() {
+    #[cfg(not(target_arch = "wasm32"))]
+    if lifetime_stats::export_from_command_line() {
+        return;
+    }
+
     let mut app = App::new();
     app.add_plugins(DefaultPlugins)
         .add_state(GameState::Game)
         .insert_resource(ClearColor(Color::BLACK))
         .add_startup_system(setup)
+        .add_plugin(controls::ControlsPlugin)
+        .add_plugin(collision::CollisionPlugin)
+        .add_plugin(wrap::WrapPlugin)
+        .add_plugin(sprite_shapes::SpriteShapesPlugin)
         .add_plugin(gifteroids::GifteroidsPlugin)
         .add_plugin(spaceship::SpaceshipPlugin)
         .add_plugin(ui::UiPlugin)
         .add_plugin(score::ScorePlugin)
         .add_plugin(santa::SantaPlugin)
+        .add_plugin(powerups::PowerUpPlugin)
+        .add_plugin(lifetime_stats::LifetimeStatsPlugin)
+        .add_plugin(achievements::AchievementsPlugin)
         .add_system_set(SystemSet::on_enter(GameState::Game).with_system(despawn_on_enter))
         .add_system_set(SystemSet::on_enter(GameState::GameOver).with_system(despawn_on_enter))
         .add_system_set(SystemSet::on_enter(GameState::Highscore).with_system(despawn_on_enter))
-        .add_system_set(SystemSet::on_update(GameState::Game).with_system(move_objects))
-        .add_system_set(SystemSet::on_update(GameState::GameOver).with_system(move_objects));
+        .add_system_set(SystemSet::on_enter(GameState::LifetimeStats).with_system(despawn_on_enter))
+        .add_system_set(
+            SystemSet::on_update(GameState::Game)
+                .with_system(move_objects.label(MovementSystem))
+                .with_system(rotate_objects.label(MovementSystem)),
+        )
+        .add_system_set(
+            SystemSet::on_update(GameState::GameOver)
+                .with_system(move_objects.label(MovementSystem))
+                .with_system(rotate_objects.label(MovementSystem)),
+        );
 
     #[cfg(feature = "debug_lines")]
     app.add_plugin(debug_lines::DebugLinesPlugin);
@@ -75,3 +109,13 @@ fn move_objects(time: Res<Time>, mut query: Query<(&mut Transform, &MovementSpee
         transform.translation += Vec3::from((speed.0 * time.delta_seconds(), 0.0));
     }
 }
+
+/// Rotation speed in radians per second, counter-clockwise.
+#[derive(Component)]
+pub struct AngularVelocity(pub f32);
+
+fn rotate_objects(time: Res<Time>, mut query: Query<(&mut Transform, &AngularVelocity)>) {
+    for (mut transform, angular_velocity) in &mut query {
+        transform.rotate_z(angular_velocity.0 * time.delta_seconds());
+    }
+}
@@ -0,0 +1,287 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    collision::{Collider, ColliderShape, CollisionEvent, CollisionLayer, CollisionSystem},
+    gifteroids::GifteroidDestroyedEvent,
+    santa::SantaDestroyedEvent,
+    spaceship::{SpaceShip, SpaceShipDestroyedEvent, SpaceShipState},
+    wrap::ScreenWrap,
+    DespawnOnStateEnter, GameState, MovementSpeed,
+};
+
+pub struct PowerUpPlugin;
+
+impl Plugin for PowerUpPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PowerUpCollectedEvent>()
+            .add_startup_system(on_load)
+            .add_system_set(SystemSet::on_enter(GameState::Game).with_system(reset_power_ups))
+            .add_system_set(
+                SystemSet::on_update(GameState::Game)
+                    .with_system(drop_power_ups)
+                    .with_system(collect_power_ups.after(CollisionSystem))
+                    .with_system(power_up_timeout)
+                    .with_system(tick_active_power_ups)
+                    .with_system(on_space_ship_destroy),
+            );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PowerUpKind {
+    SpreadShot,
+    RapidFire,
+    Shield,
+    ExtraLife,
+    ScoreMultiplier,
+}
+
+pub struct PowerUpDefinition {
+    pub kind: PowerUpKind,
+    pub name: &'static str,
+    pub color: Color,
+    /// Effect duration, instant effects have zero duration.
+    pub duration_seconds: f32,
+    /// Relative chance of being picked when a power-up drops.
+    pub drop_weight: u32,
+}
+
+pub const POWER_UPS: [PowerUpDefinition; 5] = [
+    PowerUpDefinition {
+        kind: PowerUpKind::SpreadShot,
+        name: "Spread",
+        color: Color::GOLD,
+        duration_seconds: 10.0,
+        drop_weight: 3,
+    },
+    PowerUpDefinition {
+        kind: PowerUpKind::RapidFire,
+        name: "Rapid",
+        color: Color::ORANGE_RED,
+        duration_seconds: 10.0,
+        drop_weight: 3,
+    },
+    PowerUpDefinition {
+        kind: PowerUpKind::Shield,
+        name: "Shield",
+        color: Color::CYAN,
+        duration_seconds: 8.0,
+        drop_weight: 2,
+    },
+    PowerUpDefinition {
+        kind: PowerUpKind::ExtraLife,
+        name: "Life",
+        color: Color::LIME_GREEN,
+        duration_seconds: 0.0,
+        drop_weight: 1,
+    },
+    PowerUpDefinition {
+        kind: PowerUpKind::ScoreMultiplier,
+        name: "x2",
+        color: Color::FUCHSIA,
+        duration_seconds: 15.0,
+        drop_weight: 2,
+    },
+];
+
+impl PowerUpKind {
+    pub fn definition(&self) -> &'static PowerUpDefinition {
+        POWER_UPS
+            .iter()
+            .find(|definition| definition.kind == *self)
+            .unwrap()
+    }
+}
+
+const GIFTEROID_DROP_CHANCE: f64 = 0.05;
+const PICKUP_SPEED: f32 = 40.0;
+const PICKUP_HALF_EXTENT: f32 = 16.0;
+const PICKUP_LIFE_TIME: Duration = Duration::from_secs(10);
+const PICKUP_BLINK_TIME: Duration = Duration::from_secs(3);
+
+pub struct PowerUpCollectedEvent {
+    pub kind: PowerUpKind,
+}
+
+/// Timed effects currently granted to the player.
+#[derive(Resource, Default)]
+pub struct ActivePowerUps(Vec<(PowerUpKind, Duration)>);
+
+impl ActivePowerUps {
+    pub fn is_active(&self, kind: PowerUpKind) -> bool {
+        self.time_left(kind).is_some()
+    }
+
+    pub fn time_left(&self, kind: PowerUpKind) -> Option<Duration> {
+        self.0
+            .iter()
+            .find(|(active_kind, _)| *active_kind == kind)
+            .map(|(_, time_left)| *time_left)
+    }
+
+    fn activate(&mut self, kind: PowerUpKind, duration: Duration) {
+        self.0.retain(|(active_kind, _)| *active_kind != kind);
+        self.0.push((kind, duration));
+    }
+}
+
+#[derive(Component)]
+struct PowerUp {
+    kind: PowerUpKind,
+    spawn_time: Duration,
+}
+
+#[derive(Resource)]
+struct PowerUpSprite(Handle<Image>);
+
+#[derive(Resource)]
+struct PowerUpRng(StdRng);
+
+fn on_load(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(PowerUpSprite(asset_server.load("snowball.png")));
+}
+
+fn reset_power_ups(mut commands: Commands, time: Res<Time>) {
+    commands.insert_resource(ActivePowerUps::default());
+    // Seeded from the time so every round drops different power-ups.
+    commands.insert_resource(PowerUpRng(StdRng::seed_from_u64(
+        time.elapsed().as_micros() as u64,
+    )));
+}
+
+fn drop_power_ups(
+    mut commands: Commands,
+    mut gifteroid_events: EventReader<GifteroidDestroyedEvent>,
+    mut santa_events: EventReader<SantaDestroyedEvent>,
+    mut rng: ResMut<PowerUpRng>,
+    sprite: Res<PowerUpSprite>,
+    time: Res<Time>,
+) {
+    let rng = &mut rng.0;
+    let gifteroid_drops = gifteroid_events
+        .iter()
+        .filter(|_| rng.gen_bool(GIFTEROID_DROP_CHANCE))
+        .map(|event| event.position)
+        .collect::<Vec<_>>();
+    let santa_drops = santa_events.iter().map(|event| event.position);
+
+    for position in gifteroid_drops.into_iter().chain(santa_drops) {
+        let total_weight = POWER_UPS.iter().map(|p| p.drop_weight).sum::<u32>();
+        let mut pick = rng.gen_range(0..total_weight);
+        let definition = POWER_UPS
+            .iter()
+            .find(|definition| {
+                if pick < definition.drop_weight {
+                    true
+                } else {
+                    pick -= definition.drop_weight;
+                    false
+                }
+            })
+            .unwrap();
+
+        let movement_angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        commands
+            .spawn(SpriteBundle {
+                texture: sprite.0.clone(),
+                sprite: Sprite {
+                    color: definition.color,
+                    custom_size: Some(Vec2::splat(PICKUP_HALF_EXTENT * 2.0)),
+                    ..default()
+                },
+                transform: Transform::from_translation(position.extend(0.0)),
+                ..default()
+            })
+            .insert(PowerUp {
+                kind: definition.kind,
+                spawn_time: time.elapsed(),
+            })
+            .insert(MovementSpeed(
+                Vec2::new(movement_angle.cos(), movement_angle.sin()) * PICKUP_SPEED,
+            ))
+            .insert(Collider::new(
+                ColliderShape::OrientedBox {
+                    half_extents: Vec2::splat(PICKUP_HALF_EXTENT),
+                },
+                CollisionLayer::PowerUp,
+            ))
+            .insert(ScreenWrap {
+                radius: PICKUP_HALF_EXTENT * std::f32::consts::SQRT_2,
+            })
+            .insert(DespawnOnStateEnter(GameState::Game));
+    }
+}
+
+fn collect_power_ups(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut query_spaceship: Query<&mut SpaceShip>,
+    query_power_ups: Query<&PowerUp>,
+    mut active_power_ups: ResMut<ActivePowerUps>,
+    mut collected_events: EventWriter<PowerUpCollectedEvent>,
+) {
+    for event in collision_events.iter() {
+        let Some((entity, entity_spaceship)) = event.sorted(|e| query_power_ups.contains(e)) else {
+            continue;
+        };
+        let Ok(mut spaceship) = query_spaceship.get_mut(entity_spaceship) else {
+            continue;
+        };
+        if matches!(spaceship.state, SpaceShipState::Destroyed) {
+            continue;
+        }
+        let power_up = query_power_ups.get(entity).unwrap();
+
+        commands.entity(entity).despawn();
+
+        let definition = power_up.kind.definition();
+        if definition.duration_seconds > 0.0 {
+            active_power_ups.activate(
+                power_up.kind,
+                Duration::from_secs_f32(definition.duration_seconds),
+            );
+        }
+        if power_up.kind == PowerUpKind::ExtraLife {
+            spaceship.lives_left += 1;
+        }
+        collected_events.send(PowerUpCollectedEvent {
+            kind: power_up.kind,
+        });
+    }
+}
+
+fn power_up_timeout(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &PowerUp, &mut Visibility)>,
+) {
+    for (entity, power_up, mut visibility) in &mut query {
+        let age = time.elapsed() - power_up.spawn_time;
+        if age > PICKUP_LIFE_TIME {
+            commands.entity(entity).despawn();
+        } else if age > PICKUP_LIFE_TIME - PICKUP_BLINK_TIME {
+            visibility.is_visible = (time.elapsed_seconds() * 8.0).fract() > 0.5;
+        }
+    }
+}
+
+fn tick_active_power_ups(time: Res<Time>, mut active_power_ups: ResMut<ActivePowerUps>) {
+    for (_, time_left) in active_power_ups.0.iter_mut() {
+        *time_left = time_left.saturating_sub(time.delta());
+    }
+    active_power_ups
+        .0
+        .retain(|(_, time_left)| !time_left.is_zero());
+}
+
+fn on_space_ship_destroy(
+    mut destroyed_events: EventReader<SpaceShipDestroyedEvent>,
+    mut active_power_ups: ResMut<ActivePowerUps>,
+) {
+    if destroyed_events.iter().next().is_some() {
+        active_power_ups.0.clear();
+    }
+}
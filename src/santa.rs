@@ -4,12 +4,19 @@ use bevy::prelude::*;
 use rand::{rngs::StdRng, Rng, SeedableRng};
 
 use crate::{
-    gifteroids::{spawn_gifteroid, GiftSprites, GifteroidSize},
+    collision::{CollisionEvent, CollisionLayer, CollisionSystem},
+    gifteroids::{
+        spawn_gifteroid, GiftSprite, GiftType, GifteroidSize, GifteroidSpawn, SIZE_TIERS,
+    },
     spaceship::Snowball,
+    sprite_shapes::ColliderFromSprite,
     DespawnOnStateEnter, GameState, MovementSpeed,
 };
 
-const SANTA_SPRITE_SIZE: f32 = 128.0;
+/// How far outside the screen Santas enter and leave, needs to fit the whole sprite.
+const SANTA_OFFSCREEN_MARGIN: f32 = 128.0;
+/// Santas drop the second largest size tier, or the only one if there is just one.
+const SANTA_GIFTEROID_SIZE: GifteroidSize = GifteroidSize(if SIZE_TIERS.len() > 1 { 1 } else { 0 });
 
 pub struct SantaPlugin;
 
@@ -21,7 +28,7 @@ impl Plugin for SantaPlugin {
             .add_system_set(
                 SystemSet::on_update(GameState::Game)
                     .with_system(santa_shoot)
-                    .with_system(santa_snowball_collision)
+                    .with_system(santa_snowball_collision.after(CollisionSystem))
                     .with_system(santa_spawn)
                     .with_system(santa_despawn),
             );
@@ -41,9 +48,16 @@ struct SantaSpawner {
 struct Santa {
     last_gifteroid_spawn: Duration,
     gen_number: u64,
+    /// Where the flight across the screen starts and ends.
+    start_x: f32,
+    end_x: f32,
 }
 
-pub struct SantaDestroyedEvent;
+pub struct SantaDestroyedEvent {
+    pub position: Vec2,
+    /// From 0 when entering the screen to 1 when leaving it.
+    pub flight_progress: f32,
+}
 
 fn on_load(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands.insert_resource(SantaSprite(asset_server.load("santa.png")));
@@ -60,7 +74,7 @@ fn santa_shoot(
     mut commands: Commands,
     mut query: Query<(&Transform, &mut Santa)>,
     time: Res<Time>,
-    gift_sprite: Res<GiftSprites>,
+    gift_sprite: Res<GiftSprite>,
 ) {
     const SECONDS_BETWEEN_GIFTEROID_SPAWN: f32 = 0.8;
 
@@ -73,12 +87,19 @@ fn santa_shoot(
 
             let mut rng = StdRng::seed_from_u64(santa.gen_number);
 
+            let gift_type = GiftType::random(&mut rng);
+            let movement = SANTA_GIFTEROID_SIZE.random_movement(&mut rng);
             spawn_gifteroid(
                 &mut commands,
-                gift_sprite.gift0.clone(),
-                transform.translation.truncate(),
+                gift_sprite.0.clone(),
+                time.elapsed(),
+                GifteroidSpawn {
+                    position: transform.translation.truncate(),
+                    movement,
+                    size: SANTA_GIFTEROID_SIZE,
+                    gift_type,
+                },
                 &mut rng,
-                GifteroidSize::Medium,
             );
         }
     }
@@ -86,24 +107,35 @@ fn santa_shoot(
 
 fn santa_snowball_collision(
     mut commands: Commands,
-    query_snowballs: Query<(Entity, &Transform), With<Snowball>>,
-    query_santa: Query<(Entity, &Transform), With<Santa>>,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut query_snowballs: Query<&mut Snowball>,
+    query_santa: Query<(&Transform, &Santa)>,
     mut destroyed_events: EventWriter<SantaDestroyedEvent>,
 ) {
-    for (entity_santa, transform_santa) in &query_santa {
-        let aabb = Rect::from_center_half_size(
-            transform_santa.translation.truncate(),
-            Vec2::new(SANTA_SPRITE_SIZE * 0.5, SANTA_SPRITE_SIZE * 0.125),
-        );
-
-        for (entity_snowball, transform_snowball) in &query_snowballs {
-            if aabb.contains(transform_snowball.translation.truncate()) {
-                commands.entity(entity_santa).despawn();
-                commands.entity(entity_snowball).despawn();
-
-                destroyed_events.send(SantaDestroyedEvent);
-            }
+    let mut destroyed = Vec::new();
+    for event in collision_events.iter() {
+        let Some((entity_santa, entity_snowball)) = event.sorted(|e| query_santa.contains(e))
+        else {
+            continue;
+        };
+        let Ok(mut snowball) = query_snowballs.get_mut(entity_snowball) else {
+            continue;
+        };
+        if snowball.hits_left == 0 || destroyed.contains(&entity_santa) {
+            continue;
         }
+
+        commands.entity(entity_santa).despawn();
+        destroyed.push(entity_santa);
+        snowball.hits_left -= 1;
+
+        let (transform, santa) = query_santa.get(entity_santa).unwrap();
+        destroyed_events.send(SantaDestroyedEvent {
+            position: transform.translation.truncate(),
+            flight_progress: ((transform.translation.x - santa.start_x)
+                / (santa.end_x - santa.start_x))
+                .clamp(0.0, 1.0),
+        });
     }
 }
 
@@ -133,7 +165,7 @@ fn santa_spawn(
         y: window.height(),
     };
 
-    let x_pos = (screen_size.x + SANTA_SPRITE_SIZE) * 0.5;
+    let x_pos = (screen_size.x + SANTA_OFFSCREEN_MARGIN) * 0.5;
     let (x_pos, movement) = if left {
         (-x_pos, Vec2::new(SANTA_SPEED, 0.0))
     } else {
@@ -147,7 +179,8 @@ fn santa_spawn(
                 translation: Vec3::new(
                     x_pos,
                     rng.gen_range(
-                        (-screen_size.y + SANTA_SPRITE_SIZE)..(screen_size.y - SANTA_SPRITE_SIZE),
+                        (-screen_size.y + SANTA_OFFSCREEN_MARGIN)
+                            ..(screen_size.y - SANTA_OFFSCREEN_MARGIN),
                     ) * 0.5,
                     0.0,
                 ),
@@ -157,9 +190,12 @@ fn santa_spawn(
             ..default()
         })
         .insert(MovementSpeed(movement))
+        .insert(ColliderFromSprite::new(CollisionLayer::Santa, &[]))
         .insert(Santa {
             last_gifteroid_spawn: time.elapsed(),
             gen_number: spawn_timer.gen_number * 10,
+            start_x: x_pos,
+            end_x: -x_pos,
         })
         .insert(DespawnOnStateEnter(GameState::Game));
 }
@@ -176,8 +212,8 @@ fn santa_despawn(
     };
 
     for (entity, transform) in &query {
-        if transform.translation.x > screen_size.x * 0.5 + SANTA_SPRITE_SIZE
-            || transform.translation.x < -screen_size.x * 0.5 - SANTA_SPRITE_SIZE
+        if transform.translation.x > screen_size.x * 0.5 + SANTA_OFFSCREEN_MARGIN
+            || transform.translation.x < -screen_size.x * 0.5 - SANTA_OFFSCREEN_MARGIN
         {
             commands.entity(entity).despawn();
         }
@@ -1,28 +1,53 @@
+use std::collections::HashSet;
+
 use bevy::{prelude::*, time::FixedTimestep};
 
 use crate::{
-    gifteroids::GifteroidDestroyedEvent, santa::SantaDestroyedEvent,
-    spaceship::SpaceShipDestroyedEvent, GameState,
+    gifteroids::{GifteroidDestroyedEvent, SIZE_TIERS},
+    powerups::{ActivePowerUps, PowerUpKind},
+    santa::SantaDestroyedEvent,
+    spaceship::{Snowball, SnowballHitEvent, SnowballMissedEvent, SpaceShipDestroyedEvent},
+    GameState,
 };
 
 pub struct ScorePlugin;
 
-const START_SCORE: u32 = 1000;
+pub const START_SCORE: u32 = 1000;
 const SCORE_REDUCTION_FREQUENCY_SECONDS: f64 = 1.0;
 const SCORE_REDUCTION: u32 = 10;
-const SCORE_PER_GIFTEROID: u32 = 10;
 const SCORE_PER_SANTA: u32 = 150;
+/// Extra points for a Santa hit right as he enters the screen, down to none as he leaves.
+const SCORE_SANTA_EARLY_BONUS: u32 = 150;
+/// Snowballs that flew at least this far before hitting earn a bonus.
+const LONG_RANGE_SHOT_DISTANCE: f32 = 500.0;
+const SCORE_LONG_RANGE_BONUS: u32 = 10;
+/// Gifteroids destroyed within this many seconds after they appeared earn a bonus.
+const QUICK_KILL_SECONDS: f32 = 1.0;
+const SCORE_QUICK_KILL_BONUS: u32 = 10;
 const SCORE_LOSS_PER_LIFE_LOST: u32 = 100;
+const SCORE_MULTIPLIER_POWER_UP: u32 = 2;
+/// Seconds after a hit in which the next one has to land to keep the combo going.
+const COMBO_WINDOW_SECONDS: f32 = 2.0;
+/// The combo multiplier goes up by one for every this many hits.
+const COMBO_HITS_PER_MULTIPLIER: u32 = 5;
+const COMBO_MAX_MULTIPLIER: u32 = 5;
 
 impl Plugin for ScorePlugin {
     fn build(&self, app: &mut App) {
-        app.add_startup_system(setup)
+        app.add_event::<ScoreChangedEvent>()
+            .add_startup_system(setup)
             .add_system_set(SystemSet::on_enter(GameState::Game).with_system(setup))
             .add_system_set(
                 SystemSet::on_update(GameState::Game)
+                    .label(RoundStatsSystem)
                     .with_system(on_asteroid_destroyed)
                     .with_system(on_santa_destroyed)
-                    .with_system(on_spaceship_destroyed),
+                    .with_system(on_spaceship_destroyed)
+                    .with_system(on_snowball_missed)
+                    .with_system(on_snowball_hit)
+                    .with_system(update_combo)
+                    .with_system(count_shots)
+                    .with_system(track_round_time),
             )
             .add_system_set(
                 SystemSet::on_update(GameState::Game)
@@ -32,37 +57,218 @@ impl Plugin for ScorePlugin {
     }
 }
 
+/// Updates the [`RoundStats`] from gameplay events, systems reading them should run after it.
+#[derive(SystemLabel)]
+pub struct RoundStatsSystem;
+
 #[derive(Resource)]
 pub struct Score(pub u32);
 
+/// Points gained or lost for something that happened at a position in the world.
+///
+/// Not sent for the score draining over time.
+pub struct ScoreChangedEvent {
+    pub points: i32,
+    pub position: Vec2,
+}
+
+impl Score {
+    fn add(&mut self, points: u32, position: Vec2, events: &mut EventWriter<ScoreChangedEvent>) {
+        self.0 += points;
+        events.send(ScoreChangedEvent {
+            points: points as i32,
+            position,
+        });
+    }
+
+    /// Returns how many points were actually lost, the score doesn't go below zero.
+    /// Nothing is sent if no points were lost.
+    fn subtract(
+        &mut self,
+        points: u32,
+        position: Vec2,
+        events: &mut EventWriter<ScoreChangedEvent>,
+    ) -> u32 {
+        let lost = points.min(self.0);
+        if lost > 0 {
+            self.0 -= lost;
+            events.send(ScoreChangedEvent {
+                points: -(lost as i32),
+                position,
+            });
+        }
+        lost
+    }
+}
+
+/// What happened during the current round, for the breakdown at the end.
+#[derive(Resource, Default)]
+pub struct RoundStats {
+    /// Per size tier.
+    pub gifts_destroyed: [u32; SIZE_TIERS.len()],
+    pub gift_points: [u32; SIZE_TIERS.len()],
+    /// Gifts caught in explosions rather than hit directly.
+    pub explosion_kills: u32,
+    pub santas_downed: u32,
+    pub santa_points: u32,
+    pub lives_lost: u32,
+    pub life_loss_points: u32,
+    /// Lost by the score going down over time.
+    pub points_drained: u32,
+    pub seconds: f32,
+    pub shots_fired: u32,
+    /// Snowballs that hit anything, the others count as misses including those still flying.
+    pub hits: u32,
+}
+
+impl RoundStats {
+    /// Fraction of snowballs that hit something, none before the first shot.
+    pub fn accuracy(&self) -> Option<f32> {
+        (self.shots_fired > 0)
+            .then(|| self.hits.min(self.shots_fired) as f32 / self.shots_fired as f32)
+    }
+}
+
+/// Hits in quick succession, raises the score multiplier.
+#[derive(Resource, Default)]
+pub struct Combo {
+    pub hits: u32,
+    /// Seconds left to land the next hit.
+    pub time_left: f32,
+    /// Most hits in a single combo this round.
+    pub best: u32,
+}
+
+impl Combo {
+    pub fn multiplier(&self) -> u32 {
+        (1 + self.hits / COMBO_HITS_PER_MULTIPLIER).min(COMBO_MAX_MULTIPLIER)
+    }
+
+    fn hit(&mut self) {
+        self.hits += 1;
+        self.time_left = COMBO_WINDOW_SECONDS;
+        self.best = self.best.max(self.hits);
+    }
+
+    fn reset(&mut self) {
+        self.hits = 0;
+        self.time_left = 0.0;
+    }
+}
+
 fn setup(mut commands: Commands) {
     commands.insert_resource(Score(START_SCORE));
+    commands.insert_resource(Combo::default());
+    commands.insert_resource(RoundStats::default());
+}
+
+fn reduce_score(mut score: ResMut<Score>, mut stats: ResMut<RoundStats>) {
+    let drained = SCORE_REDUCTION.min(score.0);
+    score.0 -= drained;
+    stats.points_drained += drained;
+}
+
+fn track_round_time(time: Res<Time>, mut stats: ResMut<RoundStats>) {
+    stats.seconds += time.delta_seconds();
+}
+
+fn count_shots(query: Query<(), Added<Snowball>>, mut stats: ResMut<RoundStats>) {
+    stats.shots_fired += query.iter().count() as u32;
+}
+
+fn score_multiplier(active_power_ups: &ActivePowerUps, combo: &Combo) -> u32 {
+    let power_up_multiplier = if active_power_ups.is_active(PowerUpKind::ScoreMultiplier) {
+        SCORE_MULTIPLIER_POWER_UP
+    } else {
+        1
+    };
+    power_up_multiplier * combo.multiplier()
+}
+
+fn update_combo(time: Res<Time>, mut combo: ResMut<Combo>) {
+    if combo.hits == 0 {
+        return;
+    }
+    combo.time_left -= time.delta_seconds();
+    if combo.time_left <= 0.0 {
+        combo.reset();
+    }
 }
 
-fn reduce_score(mut score: ResMut<Score>) {
-    score.0 = score.0.saturating_sub(SCORE_REDUCTION);
+fn on_snowball_missed(mut events: EventReader<SnowballMissedEvent>, mut combo: ResMut<Combo>) {
+    if events.iter().count() > 0 {
+        combo.reset();
+    }
+}
+
+fn on_snowball_hit(mut events: EventReader<SnowballHitEvent>, mut stats: ResMut<RoundStats>) {
+    stats.hits += events.iter().count() as u32;
 }
 
 fn on_asteroid_destroyed(
     mut events: EventReader<GifteroidDestroyedEvent>,
     mut score: ResMut<Score>,
+    mut combo: ResMut<Combo>,
+    mut stats: ResMut<RoundStats>,
+    active_power_ups: Res<ActivePowerUps>,
+    mut score_events: EventWriter<ScoreChangedEvent>,
 ) {
-    for _ in events.iter() {
-        score.0 += SCORE_PER_GIFTEROID;
+    // Gifteroids caught in an explosion are sent in the same frame after the exploding one,
+    // so they were destroyed by a gifteroid seen before.
+    let mut destroyed = HashSet::new();
+    for event in events.iter() {
+        if destroyed.contains(&event.destroyed_by) {
+            stats.explosion_kills += 1;
+        }
+        destroyed.insert(event.entity);
+        combo.hit();
+        let mut points = event.gift_type.definition().score * event.size.tier().score_multiplier;
+        if event
+            .shot_distance
+            .map_or(false, |distance| distance >= LONG_RANGE_SHOT_DISTANCE)
+        {
+            points += SCORE_LONG_RANGE_BONUS;
+        }
+        if event.age.as_secs_f32() < QUICK_KILL_SECONDS {
+            points += SCORE_QUICK_KILL_BONUS;
+        }
+        let points = points * score_multiplier(&active_power_ups, &combo);
+        score.add(points, event.position, &mut score_events);
+        stats.gifts_destroyed[event.size.0] += 1;
+        stats.gift_points[event.size.0] += points;
     }
 }
 
-fn on_santa_destroyed(mut events: EventReader<SantaDestroyedEvent>, mut score: ResMut<Score>) {
-    for _ in events.iter() {
-        score.0 += SCORE_PER_SANTA;
+fn on_santa_destroyed(
+    mut events: EventReader<SantaDestroyedEvent>,
+    mut score: ResMut<Score>,
+    mut combo: ResMut<Combo>,
+    mut stats: ResMut<RoundStats>,
+    active_power_ups: Res<ActivePowerUps>,
+    mut score_events: EventWriter<ScoreChangedEvent>,
+) {
+    for event in events.iter() {
+        combo.hit();
+        let early_bonus = SCORE_SANTA_EARLY_BONUS as f32 * (1.0 - event.flight_progress);
+        let points = (SCORE_PER_SANTA + early_bonus.round() as u32)
+            * score_multiplier(&active_power_ups, &combo);
+        score.add(points, event.position, &mut score_events);
+        stats.santas_downed += 1;
+        stats.santa_points += points;
     }
 }
 
 fn on_spaceship_destroyed(
     mut events: EventReader<SpaceShipDestroyedEvent>,
     mut score: ResMut<Score>,
+    mut combo: ResMut<Combo>,
+    mut stats: ResMut<RoundStats>,
+    mut score_events: EventWriter<ScoreChangedEvent>,
 ) {
-    for _ in events.iter() {
-        score.0 = score.0.saturating_sub(SCORE_LOSS_PER_LIFE_LOST);
+    for event in events.iter() {
+        let lost = score.subtract(SCORE_LOSS_PER_LIFE_LOST, event.position, &mut score_events);
+        combo.reset();
+        stats.lives_lost += 1;
+        stats.life_loss_points += lost;
     }
 }
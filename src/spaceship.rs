@@ -1,33 +1,63 @@
 use std::time::Duration;
 
 use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
-use crate::{DespawnOnStateEnter, GameState, MovementSpeed};
+use crate::{
+    collision::{Collider, ColliderShape, CollisionLayer, PreviousPosition},
+    controls::{ControlSettings, ShipControls},
+    powerups::{ActivePowerUps, PowerUpKind},
+    sprite_shapes::ColliderFromSprite,
+    wrap::{PlayField, ScreenWrap},
+    DespawnOnStateEnter, GameState, MovementSpeed,
+};
 
 pub struct SpaceshipPlugin;
 pub struct SpaceShipDestroyedEvent {
     pub lives_left_before_destroy: u32,
+    pub position: Vec2,
 }
 
+/// A snowball timed out without hitting anything.
+pub struct SnowballMissedEvent;
+
+/// A snowball hit something for the first time.
+pub struct SnowballHitEvent;
+
 // could ofc read this from data, but needlessly nasty to pass around
 pub const NUM_LIVES_ON_STARTUP: u32 = 4;
 pub const SPACESHIP_SPRITE_FILE: &str = "spaceship.png";
-const SPACESHIP_SPRITE_SIZE: f32 = 128.0;
+const SHIELD_BUBBLE_SIZE: f32 = 166.0;
+const SPACESHIP_SCALE: f32 = 0.5;
+const SNOWBALL_RADIUS: f32 = 8.0;
 const SPAWN_INVINCIBLE_TIMER: f32 = 2.0;
+const SHIELD_DURATION: f32 = 4.0;
+const SHIELD_RECHARGE_SECONDS: f32 = 15.0;
+const SHIELD_BREAK_INVINCIBLE_TIMER: f32 = 0.5;
+pub const HYPERSPACE_COOLDOWN_SECONDS: f32 = 5.0;
 
 impl Plugin for SpaceshipPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<SpaceShipDestroyedEvent>()
+            .add_event::<SnowballMissedEvent>()
+            .add_event::<SnowballHitEvent>()
+            // After all collisions were handled, snowballs used up by them are removed.
+            .add_system_to_stage(CoreStage::PostUpdate, snowballs_hit)
+            .init_resource::<SnowballSupplySettings>()
             .add_startup_system(on_load)
             .add_system_set(SystemSet::on_enter(GameState::Game).with_system(initial_spawn))
             .add_system_set(
                 SystemSet::on_update(GameState::Game)
                     .with_system(control_spaceship)
+                    .with_system(cycle_fire_mode.before(snowballs_shoot))
+                    .with_system(regenerate_snowball_supply.before(snowballs_shoot))
                     .with_system(snowballs_shoot)
-                    .with_system(snowballs_screen_wrap)
                     .with_system(snowballs_timeout)
                     .with_system(on_space_ship_destroy)
-                    .with_system(invincibility),
+                    .with_system(invincibility)
+                    .with_system(attach_shield_bubble)
+                    .with_system(shield)
+                    .with_system(hyperspace),
             );
     }
 }
@@ -36,25 +66,56 @@ fn on_load(mut commands: Commands, asset_server: Res<AssetServer>) {
     let space_ship_sprite = SpaceShipSprite(asset_server.load(SPACESHIP_SPRITE_FILE));
     commands.insert_resource(space_ship_sprite);
     commands.insert_resource(SnowballSprite(asset_server.load("snowball.png")));
+    commands.insert_resource(ShieldSprite(asset_server.load("snowball.png")));
 }
 
-fn initial_spawn(mut commands: Commands, space_ship_sprite: Res<SpaceShipSprite>) {
-    spawn_spaceship(&mut commands, &space_ship_sprite, NUM_LIVES_ON_STARTUP);
+fn initial_spawn(
+    mut commands: Commands,
+    space_ship_sprite: Res<SpaceShipSprite>,
+    supply_settings: Res<SnowballSupplySettings>,
+) {
+    spawn_spaceship(
+        &mut commands,
+        &space_ship_sprite,
+        &supply_settings,
+        NUM_LIVES_ON_STARTUP,
+        FireMode::Single,
+    );
 }
 
-fn spawn_spaceship(commands: &mut Commands, space_ship_sprite: &SpaceShipSprite, lives_left: u32) {
+fn spawn_spaceship(
+    commands: &mut Commands,
+    space_ship_sprite: &SpaceShipSprite,
+    supply_settings: &SnowballSupplySettings,
+    lives_left: u32,
+    fire_mode: FireMode,
+) {
     commands
         .spawn_empty()
         .insert(SpaceShip {
             state: SpaceShipState::Invincible(Duration::from_secs_f32(SPAWN_INVINCIBLE_TIMER)),
             lives_left,
+            shield_charge: 1.0,
+            hyperspace_cooldown: 0.0,
+        })
+        .insert(SnowballWeapon {
+            fire_mode,
+            supply: supply_settings.max_supply,
+            cooldown: 0.0,
+            burst_shots_left: 0,
+            charge: None,
         })
-        .insert(SnowballShootingCooldown(0.0))
         .insert(MovementSpeed(Vec2::ZERO))
+        // Radius is set once the collider is known.
+        .insert(ScreenWrap { radius: 0.0 })
+        .insert(ColliderFromSprite::new(
+            CollisionLayer::SpaceShip,
+            &[CollisionLayer::Gifteroid, CollisionLayer::PowerUp],
+        ))
         .insert(SpriteBundle {
             texture: space_ship_sprite.0.clone(),
             transform: Transform {
-                scale: Vec3::new(0.5, 0.5, 1.0),
+                scale: Vec3::new(SPACESHIP_SCALE, SPACESHIP_SCALE, 1.0),
                 ..default()
             },
             ..default()
@@ -65,6 +126,8 @@ fn spawn_spaceship(commands: &mut Commands, space_ship_sprite: &SpaceShipSprite,
 pub enum SpaceShipState {
     Normal,
     Invincible(Duration),
+    /// Absorbs the next gifteroid hit, bouncing the gifteroid away.
+    Shielded(Duration),
     Destroyed,
 }
 
@@ -72,41 +135,127 @@ pub enum SpaceShipState {
 pub struct SpaceShip {
     pub state: SpaceShipState,
     pub lives_left: u32,
+    /// Shield can be activated once this is fully charged at 1.0.
+    pub shield_charge: f32,
+    /// Seconds until the next hyperspace jump is possible.
+    pub hyperspace_cooldown: f32,
 }
 
 #[derive(Resource)]
 pub struct SpaceShipSprite(pub Handle<Image>);
 
+#[derive(Resource)]
+struct ShieldSprite(Handle<Image>);
+
+#[derive(Component)]
+struct ShieldBubble;
+
 impl SpaceShip {
-    pub fn bounding_triangle(transform: &Transform) -> (Vec2, Vec2, Vec2) {
-        let position = transform.translation.truncate();
-        let scale = transform.scale.x;
+    pub fn is_shielded(&self, active_power_ups: &ActivePowerUps) -> bool {
+        matches!(self.state, SpaceShipState::Shielded(_))
+            || active_power_ups.is_active(PowerUpKind::Shield)
+    }
+
+    /// Called when the shield absorbed a hit.
+    pub fn break_shield(&mut self) {
+        if let SpaceShipState::Shielded(_) = self.state {
+            self.state =
+                SpaceShipState::Invincible(Duration::from_secs_f32(SHIELD_BREAK_INVINCIBLE_TIMER));
+        }
+    }
+
+    /// Where snowballs are thrown from, the top of the collider or the center if there is none yet.
+    fn nose_position(transform: &Transform, collider: Option<&Collider>) -> Vec2 {
+        let nose = match collider.map(|collider| &collider.shape) {
+            Some(ColliderShape::ConvexPolygon(corners)) => Vec2::new(
+                0.0,
+                corners.iter().map(|corner| corner.y).fold(0.0, f32::max),
+            ),
+            _ => Vec2::ZERO,
+        };
+        transform.transform_point(nose.extend(0.0)).truncate()
+    }
+}
 
-        let forward = (transform.rotation * Vec3::Y).truncate();
-        let side = (transform.rotation * Vec3::X).truncate();
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum FireMode {
+    /// One snowball per trigger.
+    Single,
+    /// A quick salvo of snowballs per trigger.
+    Burst,
+    /// Hold to charge, release to throw a big snowball that goes through several gifts.
+    Charged,
+}
 
-        let a = position + forward * (SPACESHIP_SPRITE_SIZE * 0.5 * scale);
-        let b = position - (forward - side) * (SPACESHIP_SPRITE_SIZE * 0.5 * scale);
-        let c = position - (forward + side) * (SPACESHIP_SPRITE_SIZE * 0.5 * scale);
+impl FireMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FireMode::Single => "Single",
+            FireMode::Burst => "Burst",
+            FireMode::Charged => "Charged",
+        }
+    }
 
-        (a, b, c)
+    fn next(&self) -> FireMode {
+        match self {
+            FireMode::Single => FireMode::Burst,
+            FireMode::Burst => FireMode::Charged,
+            FireMode::Charged => FireMode::Single,
+        }
     }
 }
 
 #[derive(Component)]
-struct SnowballShootingCooldown(f32);
+pub struct SnowballWeapon {
+    pub fire_mode: FireMode,
+    /// Snowballs left to throw, see [`SnowballSupplySettings`].
+    pub supply: f32,
+    /// Seconds until the next snowball can be thrown, never carries over more than a frame.
+    cooldown: f32,
+    burst_shots_left: u32,
+    /// Seconds the fire button was held so far in charged mode.
+    charge: Option<f32>,
+}
+
+/// Optional limited snowball supply that regenerates over time.
+#[derive(Resource)]
+pub struct SnowballSupplySettings {
+    pub enabled: bool,
+    pub max_supply: f32,
+    pub regeneration_per_second: f32,
+}
+
+impl Default for SnowballSupplySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_supply: 12.0,
+            regeneration_per_second: 2.5,
+        }
+    }
+}
 
 #[derive(Component)]
 pub struct Snowball {
-    spawn_time: Duration,
+    pub spawn_time: Duration,
+    /// How many more things this snowball can hit before it is used up.
+    pub hits_left: u32,
+    hits: u32,
+    /// Whether the [`SnowballHitEvent`] was sent for it.
+    hit_reported: bool,
+}
+
+impl Snowball {
+    fn hit_anything(&self) -> bool {
+        self.hits_left < self.hits
+    }
 }
 
 #[derive(Resource)]
 struct SnowballSprite(Handle<Image>);
 
 fn control_spaceship(
-    camera_query: Query<&OrthographicProjection, With<Camera2d>>,
-    keyboard_input: Res<Input<KeyCode>>,
+    controls: Res<ShipControls>,
     time: Res<Time>,
     mut query: Query<(&mut MovementSpeed, &mut Transform), With<SpaceShip>>,
 ) {
@@ -120,41 +269,40 @@ fn control_spaceship(
 
     let (mut speed, mut transform) = query.single_mut();
 
-    if keyboard_input.pressed(KeyCode::Left) {
-        transform.rotate_z(ROTATION_SPEED * time.delta_seconds());
+    if controls.rotation != 0.0 {
+        transform.rotate_z(controls.rotation * ROTATION_SPEED * time.delta_seconds());
         transform.rotation = transform.rotation.normalize();
     }
-    if keyboard_input.pressed(KeyCode::Right) {
-        transform.rotate_z(-ROTATION_SPEED * time.delta_seconds());
-        transform.rotation = transform.rotation.normalize();
-    }
-    if keyboard_input.pressed(KeyCode::Up) {
-        speed.0 +=
-            transform.rotation.mul_vec3(Vec3::Y).truncate() * (ACCELERATION * time.delta_seconds());
+    if controls.thrust > 0.0 {
+        speed.0 += transform.rotation.mul_vec3(Vec3::Y).truncate()
+            * (controls.thrust * ACCELERATION * time.delta_seconds());
     }
     speed.0 *= FRICTION.powf(time.delta_seconds());
-
-    let camera = camera_query.single();
-    screen_wrap_space_ship(&mut transform, camera);
 }
 
-fn screen_wrap_space_ship(transform: &mut Transform, camera: &OrthographicProjection) {
-    let (tri_a, tri_b, tri_c) = SpaceShip::bounding_triangle(transform);
-
-    let max_x = tri_a.x.max(tri_b.x).max(tri_c.x);
-    let min_x = tri_a.x.min(tri_b.x).min(tri_c.x);
-    let max_y = tri_a.y.max(tri_b.y).max(tri_c.y);
-    let min_y = tri_a.y.min(tri_b.y).min(tri_c.y);
-
-    if max_y < camera.bottom {
-        transform.translation.y = camera.top + (transform.translation.y - min_y) - 0.1;
-    } else if min_y > camera.top {
-        transform.translation.y = camera.bottom - (max_y - transform.translation.y) + 0.1;
+fn cycle_fire_mode(controls: Res<ShipControls>, mut query: Query<&mut SnowballWeapon>) {
+    if !controls.cycle_fire_mode {
+        return;
     }
-    if max_x < camera.left {
-        transform.translation.x = camera.right + (transform.translation.x - min_x) - 0.1;
-    } else if min_x > camera.right {
-        transform.translation.x = camera.left - (max_x - transform.translation.x) + 0.1;
+    if let Ok(mut weapon) = query.get_single_mut() {
+        weapon.fire_mode = weapon.fire_mode.next();
+        weapon.burst_shots_left = 0;
+        weapon.charge = None;
+    }
+}
+
+fn regenerate_snowball_supply(
+    time: Res<Time>,
+    supply_settings: Res<SnowballSupplySettings>,
+    mut query: Query<&mut SnowballWeapon>,
+) {
+    for mut weapon in &mut query {
+        weapon.supply = if supply_settings.enabled {
+            (weapon.supply + supply_settings.regeneration_per_second * time.delta_seconds())
+                .min(supply_settings.max_supply)
+        } else {
+            supply_settings.max_supply
+        };
     }
 }
 
@@ -162,40 +310,167 @@ fn snowballs_shoot(
     mut commands: Commands,
     time: Res<Time>,
     snowball_sprite: Res<SnowballSprite>,
-    keyboard_input: Res<Input<KeyCode>>,
-    mut query: Query<(&mut SnowballShootingCooldown, &Transform)>,
+    controls: Res<ShipControls>,
+    settings: Res<ControlSettings>,
+    active_power_ups: Res<ActivePowerUps>,
+    mut query: Query<(&mut SnowballWeapon, &Transform, Option<&Collider>)>,
 ) {
     const SNOWBALL_COOLDOWN_SECONDS: f32 = 0.25;
-    const SNOWBALL_SPEED: f32 = 500.0;
+    const RAPID_FIRE_COOLDOWN_SECONDS: f32 = 0.1;
+    const SPREAD_SHOT_ANGLE: f32 = 0.2;
+    const BURST_SHOT_COUNT: u32 = 3;
+    const BURST_SHOT_INTERVAL_SECONDS: f32 = 0.06;
+    const MAX_CHARGE_SECONDS: f32 = 1.0;
+    const MAX_CHARGED_HITS: u32 = 3;
 
     if query.get_single().is_err() {
         return;
     }
 
-    let (mut cooldown, transform) = query.single_mut();
-    cooldown.0 -= time.delta_seconds();
-    cooldown.0 = cooldown.0.min(0.0);
+    let (mut weapon, transform, collider) = query.single_mut();
+    weapon.cooldown -= time.delta_seconds();
+
+    let trigger = if settings.autofire {
+        controls.fire_pressed
+    } else {
+        controls.fire_just_pressed
+    };
+    let has_supply = weapon.supply >= 1.0;
+    let cooldown_seconds = if active_power_ups.is_active(PowerUpKind::RapidFire) {
+        RAPID_FIRE_COOLDOWN_SECONDS
+    } else {
+        SNOWBALL_COOLDOWN_SECONDS
+    };
+
+    let shot = match weapon.fire_mode {
+        FireMode::Single => {
+            if weapon.cooldown <= 0.0 && trigger && has_supply {
+                weapon.cooldown += cooldown_seconds;
+                Some(0.0)
+            } else {
+                None
+            }
+        }
+        FireMode::Burst => {
+            if !has_supply {
+                weapon.burst_shots_left = 0;
+            }
+            if weapon.burst_shots_left == 0 && trigger && has_supply {
+                weapon.burst_shots_left = BURST_SHOT_COUNT;
+            }
+            if weapon.cooldown <= 0.0 && weapon.burst_shots_left > 0 {
+                weapon.burst_shots_left -= 1;
+                weapon.cooldown += if weapon.burst_shots_left > 0 {
+                    BURST_SHOT_INTERVAL_SECONDS
+                } else {
+                    cooldown_seconds
+                };
+                Some(0.0)
+            } else {
+                None
+            }
+        }
+        FireMode::Charged => {
+            // With autofire a full charge is released by itself, so holding fire keeps shooting.
+            let fully_charged = weapon
+                .charge
+                .map_or(false, |charge| charge >= MAX_CHARGE_SECONDS);
+            if controls.fire_pressed && !(settings.autofire && fully_charged) {
+                if weapon.charge.is_some() || (weapon.cooldown <= 0.0 && has_supply) {
+                    let charge = weapon.charge.unwrap_or(0.0) + time.delta_seconds();
+                    weapon.charge = Some(charge.min(MAX_CHARGE_SECONDS));
+                }
+                None
+            } else if let Some(charge) = weapon.charge.take() {
+                weapon.cooldown = weapon.cooldown.max(0.0) + cooldown_seconds;
+                Some(charge / MAX_CHARGE_SECONDS)
+            } else {
+                None
+            }
+        }
+    };
+
+    let Some(charge) = shot else {
+        // Waiting time without shooting must not pile up to several shots at once.
+        weapon.cooldown = weapon.cooldown.max(0.0);
+        return;
+    };
 
-    if cooldown.0 > 0.0 || !keyboard_input.just_pressed(KeyCode::Space) {
+    // Every hit a snowball can do costs one snowball from the supply.
+    let hits =
+        (1 + (charge * (MAX_CHARGED_HITS - 1) as f32).round() as u32).min(weapon.supply as u32);
+    if hits == 0 {
         return;
     }
+    weapon.supply -= hits as f32;
 
-    let (tri_a, _, _) = SpaceShip::bounding_triangle(transform);
+    let muzzle = Transform {
+        translation: SpaceShip::nose_position(transform, collider).extend(0.0),
+        ..*transform
+    };
+    let angles: &[f32] = if active_power_ups.is_active(PowerUpKind::SpreadShot) {
+        &[-SPREAD_SHOT_ANGLE, 0.0, SPREAD_SHOT_ANGLE]
+    } else {
+        &[0.0]
+    };
+    for angle in angles {
+        spawn_snowball(
+            &mut commands,
+            &snowball_sprite,
+            &time,
+            &muzzle,
+            *angle,
+            hits,
+            0.5 + charge * 0.5,
+        );
+    }
+}
+
+fn spawn_snowball(
+    commands: &mut Commands,
+    snowball_sprite: &SnowballSprite,
+    time: &Time,
+    transform: &Transform,
+    angle: f32,
+    hits: u32,
+    scale: f32,
+) {
+    const SNOWBALL_SPEED: f32 = 500.0;
+
+    let position = transform.translation.truncate();
 
-    cooldown.0 += SNOWBALL_COOLDOWN_SECONDS;
     commands
         .spawn_empty()
         .insert(Snowball {
             spawn_time: time.elapsed(),
+            hits_left: hits,
+            hits,
+            hit_reported: false,
+        })
+        .insert(
+            Collider::new(
+                ColliderShape::Circle {
+                    radius: SNOWBALL_RADIUS,
+                },
+                CollisionLayer::Snowball,
+            )
+            .with_mask(&[CollisionLayer::Gifteroid, CollisionLayer::Santa]),
+        )
+        .insert(PreviousPosition(position))
+        .insert(ScreenWrap {
+            radius: SNOWBALL_RADIUS * scale,
         })
         .insert(MovementSpeed(
-            transform.rotation.mul_vec3(Vec3::Y).truncate() * SNOWBALL_SPEED,
+            (transform.rotation * Quat::from_rotation_z(angle))
+                .mul_vec3(Vec3::Y)
+                .truncate()
+                * SNOWBALL_SPEED,
         ))
         .insert(SpriteBundle {
             texture: snowball_sprite.0.clone(),
             transform: Transform {
-                translation: tri_a.extend(0.0),
-                scale: Vec3::new(0.5, 0.5, 1.0),
+                translation: position.extend(0.0),
+                scale: Vec3::new(scale, scale, 1.0),
                 ..default()
             },
             ..default()
@@ -203,32 +478,11 @@ fn snowballs_shoot(
         .insert(DespawnOnStateEnter(GameState::Any));
 }
 
-fn snowballs_screen_wrap(
-    camera_query: Query<&OrthographicProjection, With<Camera2d>>,
-    mut query: Query<&mut Transform, With<Snowball>>,
-) {
-    const SNOWBALL_SIZE: f32 = 8.0;
-
-    let camera = camera_query.single();
-
-    for mut transform in &mut query {
-        if transform.translation.y + SNOWBALL_SIZE < camera.bottom {
-            transform.translation.y = camera.top + SNOWBALL_SIZE - 0.1;
-        } else if transform.translation.y - SNOWBALL_SIZE > camera.top {
-            transform.translation.y = camera.bottom - SNOWBALL_SIZE + 0.1;
-        }
-        if transform.translation.x + SNOWBALL_SIZE < camera.left {
-            transform.translation.x = camera.right + SNOWBALL_SIZE - 0.1;
-        } else if transform.translation.x - SNOWBALL_SIZE > camera.right {
-            transform.translation.x = camera.left - SNOWBALL_SIZE + 0.1;
-        }
-    }
-}
-
 fn snowballs_timeout(
     mut commands: Commands,
     time: Res<Time>,
     snowballs: Query<(Entity, &Snowball)>,
+    mut missed_events: EventWriter<SnowballMissedEvent>,
 ) {
     const SNOWBALL_MAX_LIFE_TIME: Duration = Duration::new(1, 500_000_000);
 
@@ -237,6 +491,25 @@ fn snowballs_timeout(
     for (entity, snowball) in &snowballs {
         if snowball.spawn_time < min_snowball_time {
             commands.entity(entity).despawn();
+            if !snowball.hit_anything() {
+                missed_events.send(SnowballMissedEvent);
+            }
+        }
+    }
+}
+
+fn snowballs_hit(
+    mut commands: Commands,
+    mut snowballs: Query<(Entity, &mut Snowball), Changed<Snowball>>,
+    mut hit_events: EventWriter<SnowballHitEvent>,
+) {
+    for (entity, mut snowball) in &mut snowballs {
+        if snowball.hit_anything() && !snowball.hit_reported {
+            snowball.hit_reported = true;
+            hit_events.send(SnowballHitEvent);
+        }
+        if snowball.hits_left == 0 {
+            commands.entity(entity).despawn();
         }
     }
 }
@@ -245,20 +518,23 @@ fn on_space_ship_destroy(
     mut commands: Commands,
     mut destroyed_events: EventReader<SpaceShipDestroyedEvent>,
     space_ship_sprite: Res<SpaceShipSprite>,
-    query_spaceship: Query<(Entity, With<SpaceShip>)>,
+    supply_settings: Res<SnowballSupplySettings>,
+    query_spaceship: Query<(Entity, &SnowballWeapon), With<SpaceShip>>,
 ) {
     let Some(destroyed_event) = destroyed_events.iter().next() else {
         return;
     };
 
-    let entity = query_spaceship.single().0;
-    commands.entity(entity).despawn();
+    let (entity, weapon) = query_spaceship.single();
+    commands.entity(entity).despawn_recursive();
 
     if destroyed_event.lives_left_before_destroy > 0 {
         spawn_spaceship(
             &mut commands,
             &space_ship_sprite,
+            &supply_settings,
             destroyed_event.lives_left_before_destroy - 1,
+            weapon.fire_mode,
         );
     }
 }
@@ -284,3 +560,146 @@ fn invincibility(mut query: Query<(&mut SpaceShip, &mut Sprite)>, time: Res<Time
         }
     }
 }
+
+fn attach_shield_bubble(
+    mut commands: Commands,
+    shield_sprite: Res<ShieldSprite>,
+    query: Query<Entity, Added<SpaceShip>>,
+) {
+    for entity in &query {
+        commands.entity(entity).with_children(|parent| {
+            parent
+                .spawn(SpriteBundle {
+                    texture: shield_sprite.0.clone(),
+                    sprite: Sprite {
+                        color: Color::rgba(0.5, 1.0, 1.0, 0.35),
+                        custom_size: Some(Vec2::splat(SHIELD_BUBBLE_SIZE)),
+                        ..default()
+                    },
+                    visibility: Visibility { is_visible: false },
+                    ..default()
+                })
+                .insert(ShieldBubble);
+        });
+    }
+}
+
+fn shield(
+    controls: Res<ShipControls>,
+    time: Res<Time>,
+    active_power_ups: Res<ActivePowerUps>,
+    mut query: Query<&mut SpaceShip>,
+    mut bubble_query: Query<&mut Visibility, With<ShieldBubble>>,
+) {
+    let Ok(mut ship) = query.get_single_mut() else {
+        return;
+    };
+
+    match ship.state {
+        SpaceShipState::Shielded(time_left) => {
+            ship.state = match time_left.checked_sub(time.delta()) {
+                Some(time_left) => SpaceShipState::Shielded(time_left),
+                None => SpaceShipState::Normal,
+            };
+        }
+        SpaceShipState::Normal if controls.activate_shield && ship.shield_charge >= 1.0 => {
+            ship.shield_charge = 0.0;
+            ship.state = SpaceShipState::Shielded(Duration::from_secs_f32(SHIELD_DURATION));
+        }
+        _ => {
+            ship.shield_charge =
+                (ship.shield_charge + time.delta_seconds() / SHIELD_RECHARGE_SECONDS).min(1.0);
+        }
+    }
+
+    let shielded = ship.is_shielded(&active_power_ups);
+    for mut visibility in &mut bubble_query {
+        visibility.is_visible = shielded;
+    }
+}
+
+fn hyperspace(
+    controls: Res<ShipControls>,
+    time: Res<Time>,
+    play_field: Res<PlayField>,
+    mut query: Query<(
+        &mut SpaceShip,
+        &mut Transform,
+        &mut MovementSpeed,
+        Option<&Collider>,
+    )>,
+    query_obstacles: Query<(&Transform, &Collider), Without<SpaceShip>>,
+    mut destroyed_events: EventWriter<SpaceShipDestroyedEvent>,
+) {
+    const SELF_DESTRUCTION_CHANCE: f64 = 1.0 / 16.0;
+    const ARRIVAL_INVINCIBLE_TIMER: f32 = 1.0;
+    const OBSTACLE_CLEARANCE: f32 = 40.0;
+    const MAX_PLACEMENT_ATTEMPTS: u32 = 64;
+
+    let Ok((mut ship, mut transform, mut speed, collider)) = query.get_single_mut() else {
+        return;
+    };
+    ship.hyperspace_cooldown = (ship.hyperspace_cooldown - time.delta_seconds()).max(0.0);
+    if !controls.hyperspace
+        || ship.hyperspace_cooldown > 0.0
+        || matches!(ship.state, SpaceShipState::Destroyed)
+    {
+        return;
+    }
+
+    // Compare bounding circles, plenty precise for finding an empty spot.
+    let ship_radius = collider.map_or(0.0, |collider| {
+        collider.world_shape(&transform).bounding_circle().1
+    });
+    let field = play_field.0;
+    // Nowhere to go, e.g. while the window is minimized.
+    if field.width() <= ship_radius * 2.0 || field.height() <= ship_radius * 2.0 {
+        return;
+    }
+    ship.hyperspace_cooldown = HYPERSPACE_COOLDOWN_SECONDS;
+
+    let mut rng = StdRng::seed_from_u64(time.elapsed().as_micros() as u64);
+    if rng.gen_bool(SELF_DESTRUCTION_CHANCE) {
+        ship.state = SpaceShipState::Destroyed;
+        destroyed_events.send(SpaceShipDestroyedEvent {
+            lives_left_before_destroy: ship.lives_left,
+            position: transform.translation.truncate(),
+        });
+        return;
+    }
+
+    let is_free = |position: Vec2| {
+        query_obstacles
+            .iter()
+            .all(|(obstacle_transform, collider)| {
+                let (obstacle_position, obstacle_radius) =
+                    collider.world_shape(obstacle_transform).bounding_circle();
+                // Obstacles just across an edge are close by as well.
+                play_field
+                    .wrapped_delta(position, obstacle_position)
+                    .length()
+                    > ship_radius + obstacle_radius + OBSTACLE_CLEARANCE
+            })
+    };
+    let mut random_position = || {
+        Vec2::new(
+            rng.gen_range((field.min.x + ship_radius)..(field.max.x - ship_radius)),
+            rng.gen_range((field.min.y + ship_radius)..(field.max.y - ship_radius)),
+        )
+    };
+
+    // If the field is too crowded we take the last candidate. Hyperspace is a gamble after all.
+    let mut position = random_position();
+    for _ in 1..MAX_PLACEMENT_ATTEMPTS {
+        if is_free(position) {
+            break;
+        }
+        position = random_position();
+    }
+
+    transform.translation = position.extend(transform.translation.z);
+    speed.0 = Vec2::ZERO;
+    if !matches!(ship.state, SpaceShipState::Shielded(_)) {
+        ship.state = SpaceShipState::Invincible(Duration::from_secs_f32(ARRIVAL_INVINCIBLE_TIMER));
+    }
+}
@@ -0,0 +1,123 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use bevy::math::{IVec2, Vec2};
+
+/// Uniform grid over axis aligned bounding boxes, used as collision broad phase.
+///
+pub struct SpatialGrid<T> {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<T>>,
+    /// Reused by every query, so querying doesn't allocate once they have grown large enough.
+    query_result: Vec<T>,
+    query_found: HashSet<T>,
+}
+
+impl<T: Copy + Eq + Hash> SpatialGrid<T> {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            query_result: Vec::new(),
+            query_found: HashSet::new(),
+        }
+    }
+
+    /// Removes all items but keeps the allocated cells around for the next rebuild.
+    pub fn clear(&mut self) {
+        for cell in self.cells.values_mut() {
+            cell.clear();
+        }
+    }
+
+    pub fn insert(&mut self, min: Vec2, max: Vec2, item: T) {
+        let (min_cell, max_cell) = (self.cell(min), self.cell(max));
+        for y in min_cell.y..=max_cell.y {
+            for x in min_cell.x..=max_cell.x {
+                self.cells.entry(IVec2::new(x, y)).or_default().push(item);
+            }
+        }
+    }
+
+    /// All items whose bounding box may overlap the given box, without duplicates.
+    pub fn query(&mut self, min: Vec2, max: Vec2) -> &[T] {
+        let (min_cell, max_cell) = (self.cell(min), self.cell(max));
+        self.query_result.clear();
+        self.query_found.clear();
+        for y in min_cell.y..=max_cell.y {
+            for x in min_cell.x..=max_cell.x {
+                let Some(cell) = self.cells.get(&IVec2::new(x, y)) else {
+                    continue;
+                };
+                for item in cell {
+                    if self.query_found.insert(*item) {
+                        self.query_result.push(*item);
+                    }
+                }
+            }
+        }
+        &self.query_result
+    }
+
+    fn cell(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_finds_inserted_items() {
+        let mut grid = SpatialGrid::new(10.0);
+        grid.insert(Vec2::new(1.0, 1.0), Vec2::new(2.0, 2.0), 0);
+        grid.insert(Vec2::new(51.0, 51.0), Vec2::new(52.0, 52.0), 1);
+        assert_eq!(grid.query(Vec2::new(0.0, 0.0), Vec2::new(5.0, 5.0)), [0]);
+        assert_eq!(
+            grid.query(Vec2::new(55.0, 55.0), Vec2::new(56.0, 56.0)),
+            [1]
+        );
+        assert!(grid
+            .query(Vec2::new(-30.0, -30.0), Vec2::new(-25.0, -25.0))
+            .is_empty());
+    }
+
+    #[test]
+    fn items_across_cell_boundaries_are_found_from_every_cell() {
+        let mut grid = SpatialGrid::new(10.0);
+        grid.insert(Vec2::new(-1.0, 8.0), Vec2::new(1.0, 12.0), 0);
+        for corner in [
+            Vec2::new(-5.0, 5.0),
+            Vec2::new(5.0, 5.0),
+            Vec2::new(-5.0, 15.0),
+            Vec2::new(5.0, 15.0),
+        ] {
+            assert_eq!(grid.query(corner, corner), [0]);
+        }
+    }
+
+    #[test]
+    fn query_over_several_cells_returns_items_once() {
+        let mut grid = SpatialGrid::new(10.0);
+        grid.insert(Vec2::new(-15.0, -15.0), Vec2::new(15.0, 15.0), 0);
+        grid.insert(Vec2::new(5.0, 5.0), Vec2::new(25.0, 25.0), 1);
+        let mut found = grid
+            .query(Vec2::new(-20.0, -20.0), Vec2::new(20.0, 20.0))
+            .to_vec();
+        found.sort();
+        assert_eq!(found, vec![0, 1]);
+    }
+
+    #[test]
+    fn clear_removes_all_items() {
+        let mut grid = SpatialGrid::new(10.0);
+        grid.insert(Vec2::new(1.0, 1.0), Vec2::new(2.0, 2.0), 0);
+        grid.clear();
+        assert!(grid
+            .query(Vec2::new(0.0, 0.0), Vec2::new(5.0, 5.0))
+            .is_empty());
+    }
+}
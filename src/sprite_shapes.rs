@@ -0,0 +1,294 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use bevy::{asset::HandleId, prelude::*, render::render_resource::TextureFormat};
+
+use crate::{
+    collision::{layer_mask, Collider, ColliderShape, CollisionLayer, CollisionSystem, PixelMask},
+    wrap::ScreenWrap,
+};
+
+pub struct SpriteShapesPlugin;
+
+impl Plugin for SpriteShapesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpriteShapes>()
+            .add_system(compute_sprite_shapes)
+            .add_system(
+                attach_sprite_colliders
+                    .after(compute_sprite_shapes)
+                    .before(CollisionSystem),
+            );
+    }
+}
+
+/// Pixels with at least this alpha count as solid.
+const ALPHA_THRESHOLD: u8 = 128;
+/// Side length in pixels of the squares an image is downsampled to for its pixel mask.
+const PIXEL_MASK_CELL_SIZE: usize = 4;
+
+/// Collision shapes derived from the solid pixels of the images sprites with a
+/// [`ColliderFromSprite`] use, in sprite space.
+#[derive(Resource, Default)]
+struct SpriteShapes {
+    /// `None` for images no shape can be derived from, so they are only warned about once.
+    shapes: HashMap<HandleId, Option<SpriteShape>>,
+    /// Images whose shape was derived anew this frame after they were modified.
+    modified: HashSet<HandleId>,
+}
+
+impl SpriteShapes {
+    fn get(&self, image: &Handle<Image>) -> Option<&SpriteShape> {
+        self.shapes.get(&image.id()).and_then(Option::as_ref)
+    }
+}
+
+struct SpriteShape {
+    hull: ColliderShape,
+    pixel_mask: Arc<PixelMask>,
+}
+
+impl SpriteShape {
+    fn wrap_radius(&self, transform: &Transform) -> f32 {
+        self.hull.bounding_radius() * transform.scale.abs().max_element()
+    }
+}
+
+/// Gets a [`Collider`] shaped like the entity's sprite as soon as the image is loaded.
+/// Until then the entity doesn't collide with anything, it isn't visible either.
+#[derive(Component)]
+pub struct ColliderFromSprite {
+    layer: CollisionLayer,
+    mask: u32,
+}
+
+impl ColliderFromSprite {
+    pub fn new(layer: CollisionLayer, mask: &[CollisionLayer]) -> Self {
+        Self {
+            layer,
+            mask: layer_mask(mask),
+        }
+    }
+}
+
+fn compute_sprite_shapes(
+    mut image_events: EventReader<AssetEvent<Image>>,
+    images: Res<Assets<Image>>,
+    sprites: Query<&Handle<Image>, With<ColliderFromSprite>>,
+    mut sprite_shapes: ResMut<SpriteShapes>,
+) {
+    sprite_shapes.modified.clear();
+    for event in image_events.iter() {
+        match event {
+            // Shapes are derived once a sprite needs them, see below.
+            AssetEvent::Created { .. } => {}
+            AssetEvent::Modified { handle } => {
+                if sprite_shapes.shapes.remove(&handle.id()).is_some() {
+                    sprite_shapes.modified.insert(handle.id());
+                }
+            }
+            AssetEvent::Removed { handle } => {
+                sprite_shapes.shapes.remove(&handle.id());
+            }
+        }
+    }
+
+    // Fonts, UI and other images without colliders are left alone.
+    for handle in &sprites {
+        if sprite_shapes.shapes.contains_key(&handle.id()) {
+            continue;
+        }
+        let Some(image) = images.get(handle) else {
+            continue;
+        };
+        let shape = sprite_shape(handle, image);
+        sprite_shapes.shapes.insert(handle.id(), shape);
+    }
+}
+
+fn sprite_shape(handle: &Handle<Image>, image: &Image) -> Option<SpriteShape> {
+    let Some(solid) = solid_pixels(image) else {
+        warn!("Can't derive a collision shape for image {:?}", handle);
+        return None;
+    };
+    let Some(hull) = alpha_hull(&solid) else {
+        warn!("Image {:?} has no solid pixels", handle);
+        return None;
+    };
+    Some(SpriteShape {
+        hull: ColliderShape::ConvexPolygon(hull),
+        pixel_mask: Arc::new(pixel_mask(&solid)),
+    })
+}
+
+type SpriteColliderComponents<'a> = (
+    Entity,
+    &'a Handle<Image>,
+    &'a ColliderFromSprite,
+    &'a Transform,
+    Option<&'a mut ScreenWrap>,
+);
+
+type AttachedSpriteColliderComponents<'a> = (
+    &'a Handle<Image>,
+    &'a Transform,
+    &'a mut Collider,
+    Option<&'a mut ScreenWrap>,
+);
+
+fn attach_sprite_colliders(
+    mut commands: Commands,
+    sprite_shapes: Res<SpriteShapes>,
+    mut new_sprites: Query<SpriteColliderComponents, Without<Collider>>,
+    mut attached_sprites: Query<AttachedSpriteColliderComponents, With<ColliderFromSprite>>,
+) {
+    for (entity, image, collider_from_sprite, transform, wrap) in &mut new_sprites {
+        let Some(shape) = sprite_shapes.get(image) else {
+            continue;
+        };
+        if let Some(mut wrap) = wrap {
+            wrap.radius = shape.wrap_radius(transform);
+        }
+        commands.entity(entity).insert(Collider {
+            shape: shape.hull.clone(),
+            layer: collider_from_sprite.layer,
+            mask: collider_from_sprite.mask,
+            pixel_mask: Some(shape.pixel_mask.clone()),
+        });
+    }
+
+    if sprite_shapes.modified.is_empty() {
+        return;
+    }
+    for (image, transform, mut collider, wrap) in &mut attached_sprites {
+        if !sprite_shapes.modified.contains(&image.id()) {
+            continue;
+        }
+        let Some(shape) = sprite_shapes.get(image) else {
+            continue;
+        };
+        if let Some(mut wrap) = wrap {
+            wrap.radius = shape.wrap_radius(transform);
+        }
+        collider.shape = shape.hull.clone();
+        collider.pixel_mask = Some(shape.pixel_mask.clone());
+    }
+}
+
+/// Whether each pixel of an image is solid, row by row starting at the top.
+struct SolidPixels {
+    width: usize,
+    height: usize,
+    solid: Vec<bool>,
+}
+
+impl SolidPixels {
+    fn is_solid(&self, x: usize, y: usize) -> bool {
+        self.solid[y * self.width + x]
+    }
+}
+
+fn solid_pixels(image: &Image) -> Option<SolidPixels> {
+    if !matches!(
+        image.texture_descriptor.format,
+        TextureFormat::Rgba8UnormSrgb | TextureFormat::Rgba8Unorm
+    ) {
+        return None;
+    }
+    let size = image.texture_descriptor.size;
+    Some(SolidPixels {
+        width: size.width as usize,
+        height: size.height as usize,
+        solid: image
+            .data
+            .chunks_exact(4)
+            .map(|rgba| rgba[3] >= ALPHA_THRESHOLD)
+            .collect(),
+    })
+}
+
+/// Convex hull around all solid pixels, centered like a sprite with y pointing up.
+fn alpha_hull(pixels: &SolidPixels) -> Option<Vec<Vec2>> {
+    let half_size = Vec2::new(pixels.width as f32, pixels.height as f32) * 0.5;
+
+    // Only the outermost solid pixels of each row can end up on the hull.
+    let mut points = Vec::new();
+    for y in 0..pixels.height {
+        let Some(left) = (0..pixels.width).find(|x| pixels.is_solid(*x, y)) else {
+            continue;
+        };
+        let right = (0..pixels.width)
+            .rev()
+            .find(|x| pixels.is_solid(*x, y))
+            .unwrap();
+        let (top, bottom) = (half_size.y - y as f32, half_size.y - y as f32 - 1.0);
+        let (left, right) = (left as f32 - half_size.x, right as f32 + 1.0 - half_size.x);
+        points.extend([
+            Vec2::new(left, top),
+            Vec2::new(left, bottom),
+            Vec2::new(right, top),
+            Vec2::new(right, bottom),
+        ]);
+    }
+
+    let hull = convex_hull(points);
+    (hull.len() >= 3).then_some(hull)
+}
+
+/// A mask cell is solid if any of its pixels is.
+fn pixel_mask(pixels: &SolidPixels) -> PixelMask {
+    let columns = (pixels.width + PIXEL_MASK_CELL_SIZE - 1) / PIXEL_MASK_CELL_SIZE;
+    let rows = (pixels.height + PIXEL_MASK_CELL_SIZE - 1) / PIXEL_MASK_CELL_SIZE;
+    let mut solid = vec![false; columns * rows];
+    for y in 0..pixels.height {
+        for x in 0..pixels.width {
+            if pixels.is_solid(x, y) {
+                solid[(y / PIXEL_MASK_CELL_SIZE) * columns + x / PIXEL_MASK_CELL_SIZE] = true;
+            }
+        }
+    }
+    // Cells at the right and bottom edge may be cut off, spread that over all cells so the mask
+    // covers the image exactly.
+    PixelMask::new(
+        UVec2::new(columns as u32, rows as u32),
+        Vec2::new(
+            pixels.width as f32 / columns as f32,
+            pixels.height as f32 / rows as f32,
+        ),
+        solid,
+    )
+}
+
+/// Andrew's monotone chain, returns the hull in counter-clockwise order without collinear points.
+fn convex_hull(mut points: Vec<Vec2>) -> Vec<Vec2> {
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    let mut hull: Vec<Vec2> = Vec::with_capacity(points.len() * 2);
+    for pass in 0..2 {
+        let start_len = hull.len();
+        let mut add = |point: Vec2| {
+            while hull.len() >= start_len + 2 {
+                let (a, b) = (hull[hull.len() - 2], hull[hull.len() - 1]);
+                if (b - a).perp_dot(point - a) > 0.0 {
+                    break;
+                }
+                hull.pop();
+            }
+            hull.push(point);
+        };
+        if pass == 0 {
+            points.iter().for_each(|point| add(*point));
+        } else {
+            points.iter().rev().for_each(|point| add(*point));
+        }
+        // The last point of each half is the first of the other one.
+        hull.pop();
+    }
+    hull
+}
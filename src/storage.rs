@@ -0,0 +1,83 @@
+// Keeps small text files around between sessions, in the working directory natively and in the
+// browser's local storage on the web.
+
+use bevy::prelude::{error, info};
+
+const KEY_PREFIX: &str = "gifteroids_";
+
+#[cfg(not(target_arch = "wasm32"))]
+fn path(key: &str) -> std::path::PathBuf {
+    std::path::PathBuf::from(format!("{KEY_PREFIX}{key}.json"))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load(key: &str) -> Option<String> {
+    std::fs::read_to_string(path(key)).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save(key: &str, contents: &str) {
+    if let Err(err) = std::fs::write(path(key), contents) {
+        error!("Failed to save {key}: {err}");
+    }
+}
+
+/// Writes the file to the working directory.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn export(file_name: &str, _mime_type: &str, contents: &str) {
+    match std::fs::write(file_name, contents) {
+        Ok(()) => info!("Exported {file_name}"),
+        Err(err) => error!("Failed to export {file_name}: {err}"),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn load(key: &str) -> Option<String> {
+    local_storage()?
+        .get_item(&format!("{KEY_PREFIX}{key}"))
+        .ok()
+        .flatten()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save(key: &str, contents: &str) {
+    let saved =
+        local_storage().map(|storage| storage.set_item(&format!("{KEY_PREFIX}{key}"), contents));
+    if !matches!(saved, Some(Ok(()))) {
+        error!("Failed to save {key}");
+    }
+}
+
+/// Offers the file as a download.
+#[cfg(target_arch = "wasm32")]
+pub fn export(file_name: &str, mime_type: &str, contents: &str) {
+    use wasm_bindgen::{JsCast, JsValue};
+
+    let download = || -> Result<(), JsValue> {
+        let parts = js_sys::Array::of1(&JsValue::from_str(contents));
+        let blob = web_sys::Blob::new_with_str_sequence_and_options(
+            &parts,
+            web_sys::BlobPropertyBag::new().type_(mime_type),
+        )?;
+        let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+        let document = web_sys::window()
+            .and_then(|window| window.document())
+            .ok_or("no document")?;
+        let anchor = document
+            .create_element("a")?
+            .dyn_into::<web_sys::HtmlAnchorElement>()?;
+        anchor.set_href(&url);
+        anchor.set_download(file_name);
+        anchor.click();
+        web_sys::Url::revoke_object_url(&url)
+    };
+    match download() {
+        Ok(()) => info!("Exported {file_name}"),
+        Err(err) => error!("Failed to export {file_name}: {err:?}"),
+    }
+}
@@ -4,8 +4,17 @@ use bevy::prelude::*;
 use parking_lot::Mutex;
 
 use crate::{
-    score::Score,
-    spaceship::{SpaceShipDestroyedEvent, NUM_LIVES_ON_STARTUP, SPACESHIP_SPRITE_FILE},
+    achievements::{AchievementUnlockedEvent, UnlockedAchievements, ACHIEVEMENTS},
+    collision::CollisionSettings,
+    controls::{ControlSettings, TouchControls},
+    gifteroids::{GifteroidSettings, SIZE_TIERS},
+    lifetime_stats::LifetimeStats,
+    powerups::{ActivePowerUps, PowerUpCollectedEvent, PowerUpKind, POWER_UPS},
+    score::{Combo, RoundStats, Score, ScoreChangedEvent, START_SCORE},
+    spaceship::{
+        SnowballSupplySettings, SnowballWeapon, SpaceShip, SpaceShipDestroyedEvent, SpaceShipState,
+        HYPERSPACE_COOLDOWN_SECONDS, NUM_LIVES_ON_STARTUP, SPACESHIP_SPRITE_FILE,
+    },
     web_request::{publish_score, query_highscore},
     DespawnOnStateEnter, GameState,
 };
@@ -15,19 +24,39 @@ pub struct UiPlugin;
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
         app.add_startup_system(setup)
+            .add_startup_system(setup_achievement_toasts)
+            .add_system(animate_score_popups)
+            .add_system(spawn_achievement_toasts)
+            .add_system(expire_achievement_toasts)
             .add_system_set(
                 SystemSet::on_enter(GameState::Game)
                     .with_system(setup_life_display)
-                    .with_system(setup_score_display),
+                    .with_system(setup_score_display)
+                    .with_system(setup_combo_display)
+                    .with_system(setup_fire_mode_display)
+                    .with_system(setup_snowball_supply_display)
+                    .with_system(setup_shield_display)
+                    .with_system(setup_hyperspace_display)
+                    .with_system(setup_power_up_display),
             )
             .add_system_set(
                 SystemSet::on_update(GameState::Game)
                     .with_system(on_space_ship_destroy)
-                    .with_system(score_display),
+                    .with_system(score_display)
+                    .with_system(combo_display)
+                    .with_system(spawn_score_popups)
+                    .with_system(fire_mode_display)
+                    .with_system(snowball_supply_display)
+                    .with_system(shield_display)
+                    .with_system(hyperspace_display)
+                    .with_system(on_power_up_collected)
+                    .with_system(power_up_display),
             )
             .add_system_set(SystemSet::on_enter(GameState::GameOver).with_system(show_game_over))
             .add_system_set(
-                SystemSet::on_update(GameState::GameOver).with_system(start_game_on_enter),
+                SystemSet::on_update(GameState::GameOver)
+                    .with_system(start_game_on_enter)
+                    .with_system(open_lifetime_stats),
             )
             .add_system_set(
                 SystemSet::on_enter(GameState::Highscore).with_system(setup_highscore_screen),
@@ -35,13 +64,22 @@ impl Plugin for UiPlugin {
             .add_system_set(
                 SystemSet::on_update(GameState::Highscore)
                     .with_system(start_game_on_enter)
+                    .with_system(open_lifetime_stats)
                     .with_system(check_score_query),
+            )
+            .add_system_set(
+                SystemSet::on_enter(GameState::LifetimeStats)
+                    .with_system(setup_lifetime_stats_screen),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::LifetimeStats).with_system(start_game_on_enter),
             );
     }
 }
 
 #[derive(Component)]
 struct SpaceShipLiveDisplay {
+    row: Entity,
     life_icons: Vec<Entity>,
 }
 
@@ -63,22 +101,151 @@ impl Fonts {
     }
 }
 
+/// The lifetime stats screen is keyboard only, as any tap on the round end screens starts the
+/// next round. The prompt is therefore hidden once touch input was detected.
+const LIFETIME_STATS_PROMPT: &str = "Press S for lifetime stats";
 const BACKGROUND_COLOR: BackgroundColor = BackgroundColor(Color::rgba(0.0, 0.0, 0.0, 0.5));
+const SCORE_POPUP_SECONDS: f32 = 1.0;
+const SCORE_POPUP_DRIFT_SPEED: f32 = 60.0;
+const ACHIEVEMENT_TOAST_SECONDS: f32 = 4.0;
 
 #[derive(Component)]
 struct ScoreDisplay;
 
+#[derive(Component)]
+struct ComboDisplay;
+
+#[derive(Component)]
+struct FireModeDisplay;
+
+/// Column at the top of the screen that achievement toasts are stacked in, stays across states.
+#[derive(Component)]
+struct AchievementToasts;
+
+#[derive(Component)]
+struct AchievementToast {
+    seconds_left: f32,
+}
+
+/// Points floating up from where they were earned or lost.
+#[derive(Component)]
+struct ScorePopup {
+    seconds_left: f32,
+}
+
+#[derive(Component)]
+struct SnowballSupplyDisplay;
+
+#[derive(Component)]
+struct SnowballSupplyBar;
+
+#[derive(Component)]
+struct ShieldDisplay;
+
+#[derive(Component)]
+struct ShieldBar;
+
+#[derive(Component)]
+struct HyperspaceDisplay;
+
+#[derive(Component)]
+struct HyperspaceBar;
+
+#[derive(Component)]
+struct PowerUpDisplay(PowerUpKind);
+
+#[derive(Component)]
+struct PowerUpCountdown(PowerUpKind);
+
 fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands.insert_resource(Fonts {
         font: asset_server.load("Ubuntu-Regular.ttf"),
     });
 }
 
+fn setup_achievement_toasts(mut commands: Commands) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(10.0),
+                    ..default()
+                },
+                size: Size::new(Val::Percent(100.0), Val::Auto),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(AchievementToasts);
+}
+
+fn spawn_achievement_toasts(
+    mut commands: Commands,
+    fonts: Res<Fonts>,
+    mut unlocked_events: EventReader<AchievementUnlockedEvent>,
+    container_query: Query<Entity, With<AchievementToasts>>,
+) {
+    for event in unlocked_events.iter() {
+        let toast = commands
+            .spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    margin: UiRect::all(Val::Px(5.0)),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                background_color: BACKGROUND_COLOR,
+                ..default()
+            })
+            .insert(AchievementToast {
+                seconds_left: ACHIEVEMENT_TOAST_SECONDS,
+            })
+            .with_children(|parent| {
+                parent.spawn(fonts.text(
+                    format!("Achievement unlocked: {}", event.achievement.name),
+                    30.0,
+                ));
+                parent.spawn(fonts.text(event.achievement.description, 20.0));
+            })
+            .id();
+        commands.entity(container_query.single()).add_child(toast);
+    }
+}
+
+fn expire_achievement_toasts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut AchievementToast)>,
+) {
+    for (entity, mut toast) in &mut query {
+        toast.seconds_left -= time.delta_seconds();
+        if toast.seconds_left <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn life_icon(space_ship_image: UiImage) -> ImageBundle {
+    ImageBundle {
+        style: Style {
+            size: Size::new(Val::Px(60.0), Val::Auto),
+            margin: UiRect::all(Val::Px(10.0)),
+            ..default()
+        },
+        image: space_ship_image,
+        ..default()
+    }
+}
+
 fn setup_life_display(mut commands: Commands, asset_server: Res<AssetServer>) {
     let space_ship_image = UiImage::from(asset_server.load(SPACESHIP_SPRITE_FILE));
 
     let mut life_icons = Vec::new();
-    commands
+    let row = commands
         .spawn(NodeBundle {
             style: Style {
                 align_items: AlignItems::FlexStart,
@@ -90,25 +257,14 @@ fn setup_life_display(mut commands: Commands, asset_server: Res<AssetServer>) {
         })
         .with_children(|parent| {
             life_icons = (0..NUM_LIVES_ON_STARTUP)
-                .map(|_| {
-                    parent
-                        .spawn(ImageBundle {
-                            style: Style {
-                                size: Size::new(Val::Px(60.0), Val::Auto),
-                                margin: UiRect::all(Val::Px(10.0)),
-                                ..default()
-                            },
-                            image: space_ship_image.clone(),
-                            ..default()
-                        })
-                        .id()
-                })
+                .map(|_| parent.spawn(life_icon(space_ship_image.clone())).id())
                 .collect();
         })
-        .insert(DespawnOnStateEnter(GameState::Any));
+        .insert(DespawnOnStateEnter(GameState::Any))
+        .id();
 
     commands
-        .spawn(SpaceShipLiveDisplay { life_icons })
+        .spawn(SpaceShipLiveDisplay { row, life_icons })
         .insert(DespawnOnStateEnter(GameState::Any));
 }
 
@@ -139,6 +295,154 @@ fn setup_score_display(mut commands: Commands, fonts: Res<Fonts>) {
         .insert(DespawnOnStateEnter(GameState::Any));
 }
 
+fn setup_combo_display(mut commands: Commands, fonts: Res<Fonts>) {
+    commands
+        .spawn(fonts.text("", 40.0).with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(100.0),
+                right: Val::Px(10.0),
+                ..default()
+            },
+            ..default()
+        }))
+        .insert(ComboDisplay)
+        .insert(DespawnOnStateEnter(GameState::Any));
+}
+
+fn setup_fire_mode_display(mut commands: Commands, fonts: Res<Fonts>) {
+    commands
+        .spawn(fonts.text("", 25.0).with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                bottom: Val::Px(10.0),
+                left: Val::Px(10.0),
+                ..default()
+            },
+            ..default()
+        }))
+        .insert(FireModeDisplay)
+        .insert(DespawnOnStateEnter(GameState::Any));
+}
+
+/// Meters are stacked on the right below the combo, away from the touch joystick.
+fn spawn_meter(
+    commands: &mut Commands,
+    top: f32,
+    color: Color,
+    display_marker: impl Component,
+    bar_marker: impl Component,
+) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Px(200.0), Val::Px(12.0)),
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(top),
+                    right: Val::Px(10.0),
+                    ..default()
+                },
+                ..default()
+            },
+            background_color: BACKGROUND_COLOR,
+            ..default()
+        })
+        .insert(display_marker)
+        .insert(DespawnOnStateEnter(GameState::Any))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(color),
+                    ..default()
+                })
+                .insert(bar_marker);
+        });
+}
+
+fn setup_snowball_supply_display(mut commands: Commands) {
+    spawn_meter(
+        &mut commands,
+        160.0,
+        Color::ALICE_BLUE,
+        SnowballSupplyDisplay,
+        SnowballSupplyBar,
+    );
+}
+
+fn setup_shield_display(mut commands: Commands) {
+    spawn_meter(&mut commands, 180.0, Color::CYAN, ShieldDisplay, ShieldBar);
+}
+
+fn setup_hyperspace_display(mut commands: Commands) {
+    spawn_meter(
+        &mut commands,
+        200.0,
+        Color::PURPLE,
+        HyperspaceDisplay,
+        HyperspaceBar,
+    );
+}
+
+fn setup_power_up_display(
+    mut commands: Commands,
+    fonts: Res<Fonts>,
+    asset_server: Res<AssetServer>,
+) {
+    let icon_image = UiImage::from(asset_server.load("snowball.png"));
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(100.0),
+                    left: Val::Px(10.0),
+                    ..default()
+                },
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(DespawnOnStateEnter(GameState::Any))
+        .with_children(|parent| {
+            for definition in POWER_UPS.iter().filter(|p| p.duration_seconds > 0.0) {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            display: Display::None,
+                            align_items: AlignItems::Center,
+                            margin: UiRect::all(Val::Px(5.0)),
+                            ..default()
+                        },
+                        background_color: BACKGROUND_COLOR,
+                        ..default()
+                    })
+                    .insert(PowerUpDisplay(definition.kind))
+                    .with_children(|parent| {
+                        parent.spawn(ImageBundle {
+                            style: Style {
+                                size: Size::new(Val::Px(24.0), Val::Px(24.0)),
+                                margin: UiRect::all(Val::Px(5.0)),
+                                ..default()
+                            },
+                            image: icon_image.clone(),
+                            background_color: BackgroundColor(definition.color),
+                            ..default()
+                        });
+                        parent
+                            .spawn(fonts.text("", 25.0))
+                            .insert(PowerUpCountdown(definition.kind));
+                    });
+            }
+        });
+}
+
 fn on_space_ship_destroy(
     mut commands: Commands,
     mut destroyed_events: EventReader<SpaceShipDestroyedEvent>,
@@ -164,18 +468,210 @@ fn on_space_ship_destroy(
     }
 }
 
+fn on_power_up_collected(
+    mut commands: Commands,
+    mut collected_events: EventReader<PowerUpCollectedEvent>,
+    mut life_display: Query<&mut SpaceShipLiveDisplay>,
+    asset_server: Res<AssetServer>,
+) {
+    for event in collected_events.iter() {
+        if event.kind != PowerUpKind::ExtraLife {
+            continue;
+        }
+        let display = &mut life_display.single_mut();
+        let space_ship_image = UiImage::from(asset_server.load(SPACESHIP_SPRITE_FILE));
+        let icon = commands.spawn(life_icon(space_ship_image)).id();
+        commands.entity(display.row).add_child(icon);
+        display.life_icons.push(icon);
+    }
+}
+
+fn power_up_display(
+    active_power_ups: Res<ActivePowerUps>,
+    mut row_query: Query<(&mut Style, &PowerUpDisplay)>,
+    mut text_query: Query<(&mut Text, &PowerUpCountdown)>,
+) {
+    for (mut style, display) in &mut row_query {
+        style.display = if active_power_ups.is_active(display.0) {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+    for (mut text, countdown) in &mut text_query {
+        if let Some(time_left) = active_power_ups.time_left(countdown.0) {
+            text.sections[0].value = format!(
+                "{} {:.0}s",
+                countdown.0.definition().name,
+                time_left.as_secs_f32().ceil()
+            );
+        }
+    }
+}
+
 fn score_display(score: Res<Score>, mut text_query: Query<&mut Text, With<ScoreDisplay>>) {
     let mut text = text_query.single_mut();
     text.sections[0].value = score.0.to_string();
 }
 
-fn start_game_on_enter(keys: Res<Input<KeyCode>>, mut game_state: ResMut<State<GameState>>) {
-    if keys.pressed(KeyCode::NumpadEnter) || keys.pressed(KeyCode::Return) {
+fn combo_display(combo: Res<Combo>, mut text_query: Query<&mut Text, With<ComboDisplay>>) {
+    let mut text = text_query.single_mut();
+    text.sections[0].value = if combo.hits > 0 {
+        format!(
+            "Combo {}  x{}  {:.1}s",
+            combo.hits,
+            combo.multiplier(),
+            combo.time_left.max(0.0)
+        )
+    } else {
+        String::new()
+    };
+}
+
+fn spawn_score_popups(
+    mut commands: Commands,
+    fonts: Res<Fonts>,
+    mut score_events: EventReader<ScoreChangedEvent>,
+) {
+    for event in score_events.iter() {
+        let (text, color) = if event.points >= 0 {
+            (format!("+{}", event.points), Color::WHITE)
+        } else {
+            (event.points.to_string(), Color::RED)
+        };
+        commands
+            .spawn(Text2dBundle {
+                text: Text::from_section(
+                    text,
+                    TextStyle {
+                        font: fonts.font.clone(),
+                        font_size: 30.0,
+                        color,
+                    },
+                )
+                .with_alignment(TextAlignment::CENTER),
+                // In front of everything else.
+                transform: Transform::from_translation(event.position.extend(10.0)),
+                ..default()
+            })
+            .insert(ScorePopup {
+                seconds_left: SCORE_POPUP_SECONDS,
+            })
+            .insert(DespawnOnStateEnter(GameState::Any));
+    }
+}
+
+fn animate_score_popups(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut ScorePopup, &mut Transform, &mut Text)>,
+) {
+    for (entity, mut popup, mut transform, mut text) in &mut query {
+        popup.seconds_left -= time.delta_seconds();
+        if popup.seconds_left <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation.y += SCORE_POPUP_DRIFT_SPEED * time.delta_seconds();
+        text.sections[0]
+            .style
+            .color
+            .set_a(popup.seconds_left / SCORE_POPUP_SECONDS);
+    }
+}
+
+fn fire_mode_display(
+    settings: Res<ControlSettings>,
+    collision_settings: Res<CollisionSettings>,
+    gifteroid_settings: Res<GifteroidSettings>,
+    weapon_query: Query<&SnowballWeapon>,
+    mut text_query: Query<&mut Text, With<FireModeDisplay>>,
+) {
+    let Ok(weapon) = weapon_query.get_single() else {
+        return;
+    };
+    let mut text = text_query.single_mut();
+    text.sections[0].value = format!(
+        "{} [F]   Autofire {} [A]   Pixel-perfect hits {} [P]   Bouncing gifts {} [B]",
+        weapon.fire_mode.name(),
+        if settings.autofire { "on" } else { "off" },
+        if collision_settings.pixel_perfect {
+            "on"
+        } else {
+            "off"
+        },
+        if gifteroid_settings.elastic_collisions {
+            "on"
+        } else {
+            "off"
+        }
+    );
+}
+
+fn snowball_supply_display(
+    supply_settings: Res<SnowballSupplySettings>,
+    weapon_query: Query<&SnowballWeapon>,
+    mut display_query: Query<&mut Visibility, With<SnowballSupplyDisplay>>,
+    mut bar_query: Query<&mut Style, With<SnowballSupplyBar>>,
+) {
+    display_query.single_mut().is_visible = supply_settings.enabled;
+    let Ok(weapon) = weapon_query.get_single() else {
+        return;
+    };
+    let fill = (weapon.supply / supply_settings.max_supply).clamp(0.0, 1.0);
+    bar_query.single_mut().size.width = Val::Percent(fill * 100.0);
+}
+
+fn shield_display(
+    ship_query: Query<&SpaceShip>,
+    mut bar_query: Query<(&mut Style, &mut BackgroundColor), With<ShieldBar>>,
+) {
+    let Ok(ship) = ship_query.get_single() else {
+        return;
+    };
+    let (mut style, mut color) = bar_query.single_mut();
+    let fill = match ship.state {
+        SpaceShipState::Shielded(_) => 1.0,
+        _ => ship.shield_charge,
+    };
+    style.size.width = Val::Percent(fill * 100.0);
+    // Dimmed while recharging so it's obvious when the shield is ready.
+    color.0 = if ship.shield_charge >= 1.0 {
+        Color::CYAN
+    } else {
+        Color::rgb(0.0, 0.5, 0.5)
+    };
+}
+
+fn hyperspace_display(
+    ship_query: Query<&SpaceShip>,
+    mut bar_query: Query<&mut Style, With<HyperspaceBar>>,
+) {
+    let Ok(ship) = ship_query.get_single() else {
+        return;
+    };
+    let fill = 1.0 - ship.hyperspace_cooldown / HYPERSPACE_COOLDOWN_SECONDS;
+    bar_query.single_mut().size.width = Val::Percent(fill * 100.0);
+}
+
+fn start_game_on_enter(
+    keys: Res<Input<KeyCode>>,
+    touches: Res<Touches>,
+    touch_controls: Res<TouchControls>,
+    mut game_state: ResMut<State<GameState>>,
+) {
+    if touch_controls.continue_requested(&keys, &touches) {
         game_state.overwrite_set(GameState::Game).unwrap();
     }
 }
 
-fn show_game_over(mut commands: Commands, fonts: Res<Fonts>) {
+fn open_lifetime_stats(keys: Res<Input<KeyCode>>, mut game_state: ResMut<State<GameState>>) {
+    if keys.just_pressed(KeyCode::S) {
+        game_state.overwrite_set(GameState::LifetimeStats).unwrap();
+    }
+}
+
+fn show_game_over(mut commands: Commands, fonts: Res<Fonts>, touch_controls: Res<TouchControls>) {
     commands
         .spawn(NodeBundle {
             style: Style {
@@ -199,13 +695,97 @@ fn show_game_over(mut commands: Commands, fonts: Res<Fonts>) {
                 },
             ));
             parent.spawn(TextBundle::from_section(
-                "Press Enter to try again",
+                touch_controls.continue_prompt(),
                 TextStyle {
                     font: fonts.font.clone(),
                     font_size: 50.0,
                     color: Color::WHITE,
                 },
             ));
+            if !touch_controls.detected {
+                parent.spawn(fonts.text(LIFETIME_STATS_PROMPT, 25.0));
+            }
+        });
+}
+
+fn setup_lifetime_stats_screen(
+    mut commands: Commands,
+    fonts: Res<Fonts>,
+    lifetime_stats: Res<LifetimeStats>,
+    unlocked_achievements: Res<UnlockedAchievements>,
+    touch_controls: Res<TouchControls>,
+) {
+    const RECENT_ROUNDS: usize = 10;
+
+    let percent = |fraction: f32| format!("{:.0}%", fraction * 100.0);
+    let rows = [
+        ("Rounds played", lifetime_stats.rounds_played().to_string()),
+        ("Wins", lifetime_stats.wins().to_string()),
+        ("Gifts destroyed", lifetime_stats.total_gifts().to_string()),
+        (
+            "Fastest win",
+            lifetime_stats
+                .best_time()
+                .map_or("-".to_string(), |seconds| {
+                    let seconds = seconds as u32;
+                    format!("{}:{:02}", seconds / 60, seconds % 60)
+                }),
+        ),
+        (
+            "Accuracy",
+            lifetime_stats.accuracy().map_or("-".to_string(), percent),
+        ),
+        (
+            "Achievements",
+            format!("{}/{}", unlocked_achievements.count(), ACHIEVEMENTS.len()),
+        ),
+    ];
+    let recent_accuracy = lifetime_stats
+        .recent_accuracy(RECENT_ROUNDS)
+        .into_iter()
+        .map(percent)
+        .collect::<Vec<_>>();
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                margin: UiRect::all(Val::Auto),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            background_color: BACKGROUND_COLOR,
+            ..default()
+        })
+        .insert(DespawnOnStateEnter(GameState::Any))
+        .with_children(|parent| {
+            parent.spawn(fonts.text("Lifetime Stats", 100.0));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .add_children(|parent| {
+                    spawn_score_column(&fonts, parent, rows.iter().map(|row| row.0.to_string()));
+                    spawn_score_column(&fonts, parent, rows.iter().map(|row| row.1.clone()));
+                });
+            if !recent_accuracy.is_empty() {
+                parent.spawn(fonts.text(
+                    format!(
+                        "Accuracy of the last rounds: {}",
+                        recent_accuracy.join("  ")
+                    ),
+                    25.0,
+                ));
+            }
+            if !touch_controls.detected {
+                parent.spawn(fonts.text("Export with J for JSON or C for CSV", 25.0));
+            }
+            parent.spawn(fonts.text(touch_controls.continue_prompt(), 40.0));
         });
 }
 
@@ -223,12 +803,22 @@ struct HighscoreDisplay {
     ui_entity: Entity,
 }
 
-fn setup_highscore_screen(mut commands: Commands, fonts: Res<Fonts>, score: Res<Score>) {
+fn setup_highscore_screen(
+    mut commands: Commands,
+    fonts: Res<Fonts>,
+    score: Res<Score>,
+    combo: Res<Combo>,
+    stats: Res<RoundStats>,
+    touch_controls: Res<TouchControls>,
+) {
     let ui_entity = spawn_highscore_ui(
         &mut commands,
         &fonts,
         &score,
+        &combo,
+        &stats,
         &HighscorePublishAndQueryResult::Pending,
+        &touch_controls,
     );
 
     let highscore_display = HighscoreDisplay {
@@ -277,7 +867,10 @@ fn spawn_highscore_ui(
     commands: &mut Commands,
     fonts: &Fonts,
     score: &Score,
+    combo: &Combo,
+    stats: &RoundStats,
     score_query: &HighscorePublishAndQueryResult,
+    touch_controls: &TouchControls,
 ) -> Entity {
     commands
         .spawn(NodeBundle {
@@ -295,6 +888,7 @@ fn spawn_highscore_ui(
         .with_children(|parent| {
             parent.spawn(fonts.text("You prevented christmas!", 100.0));
             parent.spawn(fonts.text(format!("Your score was {}", score.0), 40.0));
+            spawn_round_breakdown(fonts, parent, combo, stats);
             parent.spawn(fonts.text("High Score", 60.0));
 
             match score_query {
@@ -372,11 +966,87 @@ fn spawn_highscore_ui(
                 }
             }
 
-            parent.spawn(fonts.text("Press Enter to try again", 40.0));
+            parent.spawn(fonts.text(touch_controls.continue_prompt(), 40.0));
+            if !touch_controls.detected {
+                parent.spawn(fonts.text(LIFETIME_STATS_PROMPT, 25.0));
+            }
         })
         .id()
 }
 
+/// Label, count and points for everything that added to or took from the score this round.
+fn spawn_round_breakdown(
+    fonts: &Fonts,
+    parent: &mut ChildBuilder,
+    combo: &Combo,
+    stats: &RoundStats,
+) {
+    let mut rows = vec![(
+        "Starting score".to_string(),
+        String::new(),
+        START_SCORE.to_string(),
+    )];
+    for (tier, (count, points)) in SIZE_TIERS
+        .iter()
+        .zip(stats.gifts_destroyed.iter().zip(&stats.gift_points))
+    {
+        rows.push((
+            format!("{} gifts", tier.name),
+            count.to_string(),
+            format!("+{points}"),
+        ));
+    }
+    let seconds = stats.seconds as u32;
+    rows.extend([
+        (
+            "Santas downed".to_string(),
+            stats.santas_downed.to_string(),
+            format!("+{}", stats.santa_points),
+        ),
+        (
+            "Lives lost".to_string(),
+            stats.lives_lost.to_string(),
+            format!("-{}", stats.life_loss_points),
+        ),
+        (
+            "Time".to_string(),
+            format!("{}:{:02}", seconds / 60, seconds % 60),
+            format!("-{}", stats.points_drained),
+        ),
+        (
+            "Shots fired".to_string(),
+            stats.shots_fired.to_string(),
+            String::new(),
+        ),
+        (
+            "Accuracy".to_string(),
+            stats.accuracy().map_or("-".to_string(), |accuracy| {
+                format!("{:.0}%", accuracy * 100.0)
+            }),
+            String::new(),
+        ),
+        (
+            "Best combo".to_string(),
+            combo.best.to_string(),
+            String::new(),
+        ),
+    ]);
+
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                ..default()
+            },
+            ..default()
+        })
+        .add_children(|parent| {
+            spawn_score_column(fonts, parent, rows.iter().map(|row| row.0.clone()));
+            spawn_score_column(fonts, parent, rows.iter().map(|row| row.1.clone()));
+            spawn_score_column(fonts, parent, rows.iter().map(|row| row.2.clone()));
+        });
+}
+
 fn spawn_score_column(
     fonts: &Fonts,
     parent: &mut ChildBuilder,
@@ -409,6 +1079,9 @@ fn check_score_query(
     mut commands: Commands,
     fonts: Res<Fonts>,
     score: Res<Score>,
+    combo: Res<Combo>,
+    stats: Res<RoundStats>,
+    touch_controls: Res<TouchControls>,
     mut score_query: ResMut<HighscoreDisplay>,
 ) {
     if score_query.processed_query_result {
@@ -422,7 +1095,15 @@ fn check_score_query(
             return;
         }
         commands.entity(score_query.ui_entity).despawn_recursive();
-        spawn_highscore_ui(&mut commands, &fonts, &score, result)
+        spawn_highscore_ui(
+            &mut commands,
+            &fonts,
+            &score,
+            &combo,
+            &stats,
+            result,
+            &touch_controls,
+        )
     };
     score_query.processed_query_result = true;
 }
@@ -2,15 +2,15 @@ use bevy::prelude::info;
 
 // --------------------------------------------
 // ADJUST THESE BEFORE FINAL WEB RELEASE
-const DAY_INDEX: &'static str = "1";
-const GAME_SECRET: &'static str = "ApfJ2844Spg";
+const DAY_INDEX: &str = "1";
+const GAME_SECRET: &str = "ApfJ2844Spg";
 // leave this empty for final release!
-const GAME_TOKEN: &'static str = "";
-const URL: &'static str = "/";
+const GAME_TOKEN: &str = "";
+const URL: &str = "/";
 // --------------------------------------------
 
-const API_HIGHSCORE_GET: &'static str = "api/highscore/get/";
-const API_HIGHSCORE_PUBLISH: &'static str = "api/highscore/redeem";
+const API_HIGHSCORE_GET: &str = "api/highscore/get/";
+const API_HIGHSCORE_PUBLISH: &str = "api/highscore/redeem";
 
 pub fn query_highscore(on_done: impl 'static + Send + FnOnce(ehttp::Result<ehttp::Response>)) {
     info!("Querying highscore...");
@@ -0,0 +1,171 @@
+use bevy::{prelude::*, transform::TransformSystem};
+
+use crate::{collision::PreviousPosition, MovementSystem};
+
+pub struct WrapPlugin;
+
+impl Plugin for WrapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlayField>()
+            .add_system_to_stage(CoreStage::PreUpdate, update_play_field)
+            .add_system(screen_wrap.label(WrapSystem).after(MovementSystem))
+            .add_system(spawn_ghosts)
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                update_ghosts.before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+/// Moves objects that left the play field back in, runs after [`MovementSystem`].
+#[derive(SystemLabel)]
+pub struct WrapSystem;
+
+/// The visible area objects wrap around in, i.e. the play field is a torus.
+#[derive(Resource, Default)]
+pub struct PlayField(pub Rect);
+
+impl PlayField {
+    /// Offsets at which an object is visible on the play field, the object's position itself first.
+    ///
+    /// Objects close to an edge also show up on the opposite side and need to be drawn and
+    /// collision tested there as well.
+    pub fn ghost_offsets(&self, position: Vec2, radius: f32) -> Vec<Vec2> {
+        let offset = self.ghost_offset(position, radius);
+        let mut offsets = vec![Vec2::ZERO];
+        if offset.x != 0.0 {
+            offsets.push(Vec2::new(offset.x, 0.0));
+        }
+        if offset.y != 0.0 {
+            offsets.push(Vec2::new(0.0, offset.y));
+        }
+        if offset.x != 0.0 && offset.y != 0.0 {
+            offsets.push(offset);
+        }
+        offsets
+    }
+
+    /// Shortest way from one position to another, possibly across an edge.
+    pub fn wrapped_delta(&self, from: Vec2, to: Vec2) -> Vec2 {
+        let size = self.0.size();
+        let delta = to - from;
+        delta - (delta / size).round() * size
+    }
+
+    fn ghost_offset(&self, position: Vec2, radius: f32) -> Vec2 {
+        let size = self.0.size();
+        let axis_offset = |position: f32, min: f32, max: f32, size: f32| {
+            if position - radius < min {
+                size
+            } else if position + radius > max {
+                -size
+            } else {
+                0.0
+            }
+        };
+        Vec2::new(
+            axis_offset(position.x, self.0.min.x, self.0.max.x, size.x),
+            axis_offset(position.y, self.0.min.y, self.0.max.y, size.y),
+        )
+    }
+
+    /// Offset that brings a position that left the play field back in on the other side.
+    fn wrap_offset(&self, position: Vec2) -> Vec2 {
+        let size = self.0.size();
+        let axis_offset = |position: f32, min: f32, max: f32, size: f32| {
+            if position < min {
+                size
+            } else if position > max {
+                -size
+            } else {
+                0.0
+            }
+        };
+        Vec2::new(
+            axis_offset(position.x, self.0.min.x, self.0.max.x, size.x),
+            axis_offset(position.y, self.0.min.y, self.0.max.y, size.y),
+        )
+    }
+}
+
+/// Entities that wrap around the play field edges.
+#[derive(Component)]
+pub struct ScreenWrap {
+    /// Radius of a circle around the object's sprite, determines when ghosts are needed.
+    pub radius: f32,
+}
+
+/// Copy of a wrapping entity's sprite shown on the opposite side of the play field.
+#[derive(Component)]
+struct Ghost {
+    owner: Entity,
+    /// Which of the (up to three) ghost offsets this ghost shows.
+    index: usize,
+}
+
+fn update_play_field(
+    camera_query: Query<&OrthographicProjection, With<Camera2d>>,
+    mut play_field: ResMut<PlayField>,
+) {
+    if let Ok(camera) = camera_query.get_single() {
+        play_field.0 = Rect::new(camera.left, camera.bottom, camera.right, camera.top);
+    }
+}
+
+fn screen_wrap(
+    play_field: Res<PlayField>,
+    mut query: Query<(&mut Transform, Option<&mut PreviousPosition>), With<ScreenWrap>>,
+) {
+    for (mut transform, previous_position) in &mut query {
+        let offset = play_field.wrap_offset(transform.translation.truncate());
+        if offset == Vec2::ZERO {
+            continue;
+        }
+        transform.translation += offset.extend(0.0);
+        if let Some(mut previous_position) = previous_position {
+            // Keep the swept collision test from crossing the whole screen.
+            previous_position.0 += offset;
+        }
+    }
+}
+
+fn spawn_ghosts(mut commands: Commands, query: Query<(Entity, &Handle<Image>), Added<ScreenWrap>>) {
+    for (owner, texture) in &query {
+        for index in 1..=3 {
+            commands
+                .spawn(SpriteBundle {
+                    texture: texture.clone(),
+                    visibility: Visibility { is_visible: false },
+                    ..default()
+                })
+                .insert(Ghost { owner, index });
+        }
+    }
+}
+
+fn update_ghosts(
+    mut commands: Commands,
+    play_field: Res<PlayField>,
+    query_owners: Query<(&Transform, &Sprite, &Visibility, &ScreenWrap), Without<Ghost>>,
+    mut query_ghosts: Query<(Entity, &Ghost, &mut Transform, &mut Sprite, &mut Visibility)>,
+) {
+    for (entity, ghost, mut transform, mut sprite, mut visibility) in &mut query_ghosts {
+        let Ok((owner_transform, owner_sprite, owner_visibility, wrap)) =
+            query_owners.get(ghost.owner)
+        else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+
+        let offsets = play_field.ghost_offsets(owner_transform.translation.truncate(), wrap.radius);
+        let Some(offset) = offsets.get(ghost.index) else {
+            visibility.is_visible = false;
+            continue;
+        };
+
+        *transform = *owner_transform;
+        transform.translation += offset.extend(0.0);
+        *sprite = owner_sprite.clone();
+        visibility.is_visible = owner_visibility.is_visible;
+    }
+}